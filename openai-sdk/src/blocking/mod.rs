@@ -0,0 +1,173 @@
+//! A synchronous facade over the async client, for programs without an async runtime.
+//!
+//! Every client owns a single-threaded tokio runtime that drives requests to completion on the calling thread. Calling into a blocking client from within an async runtime panics; use the async [`OpenAI`](crate::OpenAI) there instead.
+
+use std::sync::Arc;
+
+#[cfg(feature = "reqwest")]
+use secrecy::SecretString;
+use tokio::runtime::Runtime;
+#[cfg(feature = "reqwest")]
+use url::Url;
+
+use crate::OpenAIError;
+use crate::transport::{
+    interceptor::{Intercepted, Interceptor},
+    rate_limit::{RateLimited, RateLimiter},
+};
+#[cfg(feature = "reqwest")]
+use crate::{config::ClientConfig, transport::StandardHttpTransport};
+
+#[cfg(feature = "responses")]
+pub mod responses;
+#[cfg(feature = "responses-streaming")]
+mod streaming;
+
+#[cfg(feature = "responses-streaming")]
+pub use streaming::EventIter;
+
+/// Blocking counterpart of [`OpenAI`](crate::OpenAI). Clones share the transport configuration and the runtime.
+#[cfg(feature = "reqwest")]
+#[derive(Clone)]
+pub struct OpenAI<T = StandardHttpTransport> {
+    inner: crate::OpenAI<T>,
+    runtime: Arc<Runtime>,
+}
+
+/// Blocking counterpart of [`OpenAI`](crate::OpenAI). Clones share the transport configuration and the runtime.
+#[cfg(not(feature = "reqwest"))]
+#[derive(Clone)]
+pub struct OpenAI<T> {
+    inner: crate::OpenAI<T>,
+    runtime: Arc<Runtime>,
+}
+
+#[cfg(feature = "reqwest")]
+impl OpenAI<StandardHttpTransport> {
+    /// Creates a client configured from the environment, see [`ClientConfig::from_env`].
+    pub fn from_env() -> Result<Self, OpenAIError> {
+        Self::from_async(crate::OpenAI::from_env()?)
+    }
+
+    /// Creates a client from a [`ClientConfig`].
+    pub fn from_config(config: &ClientConfig) -> Result<Self, OpenAIError> {
+        Self::from_async(crate::OpenAI::from_config(config)?)
+    }
+
+    /// Fails if the runtime can't be created, see [`OpenAI::from_async`].
+    pub fn standard_http(
+        access_token: SecretString,
+        client: reqwest::Client,
+    ) -> Result<Self, OpenAIError> {
+        Self::from_async(crate::OpenAI::standard_http(access_token, client))
+    }
+
+    pub fn standard_http_with_base_url(
+        access_token: SecretString,
+        client: reqwest::Client,
+        base_url: Url,
+    ) -> Result<Self, OpenAIError> {
+        Self::from_async(crate::OpenAI::standard_http_with_base_url(
+            access_token,
+            client,
+            base_url,
+        )?)
+    }
+}
+
+impl<T> OpenAI<T> {
+    /// Fails if the runtime can't be created, see [`OpenAI::from_async`].
+    pub fn new(transport: T) -> Result<Self, OpenAIError> {
+        Self::from_async(crate::OpenAI::new(transport))
+    }
+
+    /// Wraps an async client, creating a runtime to drive its requests. Fails with [`OpenAIError::Transport`] if the runtime can't be created, e.g. because the process is out of file descriptors.
+    pub fn from_async(inner: crate::OpenAI<T>) -> Result<Self, OpenAIError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|err| OpenAIError::Transport {
+                source: err.into(),
+                meta: None,
+            })?;
+
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    pub fn transport(&self) -> &T {
+        self.inner.transport()
+    }
+
+    /// Returns the wrapped async client.
+    pub fn as_async(&self) -> &crate::OpenAI<T> {
+        &self.inner
+    }
+
+    /// See [`OpenAI::with_interceptor`](crate::OpenAI::with_interceptor).
+    pub fn with_interceptor(
+        self,
+        interceptor: impl Interceptor + 'static,
+    ) -> OpenAI<Intercepted<T>> {
+        OpenAI {
+            inner: self.inner.with_interceptor(interceptor),
+            runtime: self.runtime,
+        }
+    }
+
+    /// See [`OpenAI::with_rate_limiter`](crate::OpenAI::with_rate_limiter).
+    pub fn with_rate_limiter(self, limiter: RateLimiter) -> OpenAI<RateLimited<T>> {
+        OpenAI {
+            inner: self.inner.with_rate_limiter(limiter),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Runs a future of the async client to completion, e.g. to use an API this facade does not cover yet.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    #[cfg(feature = "responses")]
+    pub fn responses<Stream>(&self) -> responses::ResponsesHandler<'_, T, Stream> {
+        responses::ResponsesHandler {
+            runtime: &self.runtime,
+            inner: self.inner.responses(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use http::{Method, StatusCode};
+    use serde_json::{Value, json};
+
+    use crate::{
+        blocking::OpenAI,
+        transport::{RequestBody, RequestOptions, Transport, mock::MockTransport},
+    };
+
+    #[test]
+    fn test_block_on() {
+        let mock = MockTransport::new();
+        mock.expect(Method::GET, "/v1/models")
+            .respond_json(StatusCode::OK, json!({ "data": [] }));
+
+        let client = OpenAI::new(mock.clone()).unwrap();
+
+        let response = client
+            .block_on(Transport::send::<_, Value>(
+                client.transport(),
+                Method::GET,
+                "/v1/models",
+                RequestBody::none(),
+                &RequestOptions::default(),
+            ))
+            .unwrap();
+        assert_eq!(response.data, json!({ "data": [] }));
+
+        mock.assert_done();
+    }
+}
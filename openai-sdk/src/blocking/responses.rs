@@ -0,0 +1,183 @@
+use std::sync::Arc;
+
+use serde_bool::False;
+use tokio::runtime::Runtime;
+
+use crate::{
+    OpenAIError,
+    models::{
+        DeletionStatus,
+        responses::{Response, ResponseId, ResponseIdRef},
+    },
+    responses::ResponseParams,
+    transport::{RequestOptions, Transport, meta::WithMeta},
+};
+
+/// Blocking counterpart of [`ResponsesHandler`](crate::responses::ResponsesHandler).
+pub struct ResponsesHandler<'a, T, Stream> {
+    pub(crate) runtime: &'a Arc<Runtime>,
+    pub(crate) inner: crate::responses::ResponsesHandler<'a, T, Stream>,
+}
+
+impl<T, Stream> ResponsesHandler<'_, T, Stream> {
+    /// Overrides the transport configuration for requests made through this handler.
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.inner = self.inner.with_options(options);
+        self
+    }
+}
+
+impl<T: Transport> ResponsesHandler<'_, T, False> {
+    /// See [`ResponsesHandler::create`](crate::responses::ResponsesHandler::create).
+    pub fn create(&self, params: &ResponseParams<False>) -> Result<Response, OpenAIError> {
+        self.runtime.block_on(self.inner.create(params))
+    }
+
+    /// Like [`Self::create`], but also returns the response metadata.
+    pub fn create_with_meta(
+        &self,
+        params: &ResponseParams<False>,
+    ) -> Result<WithMeta<Response>, OpenAIError> {
+        self.runtime.block_on(self.inner.create_with_meta(params))
+    }
+
+    /// See [`ResponsesHandler::get`](crate::responses::ResponsesHandler::get).
+    pub fn get(&self, id: &ResponseIdRef) -> Result<Response, OpenAIError> {
+        self.runtime.block_on(self.inner.get(id))
+    }
+
+    /// Like [`Self::get`], but also returns the response metadata.
+    pub fn get_with_meta(&self, id: &ResponseIdRef) -> Result<WithMeta<Response>, OpenAIError> {
+        self.runtime.block_on(self.inner.get_with_meta(id))
+    }
+}
+
+impl<T: Transport, Stream> ResponsesHandler<'_, T, Stream> {
+    /// See [`ResponsesHandler::delete`](crate::responses::ResponsesHandler::delete).
+    pub fn delete(&self, id: &ResponseIdRef) -> Result<DeletionStatus<ResponseId>, OpenAIError> {
+        self.runtime.block_on(self.inner.delete(id))
+    }
+
+    /// Like [`Self::delete`], but also returns the response metadata.
+    pub fn delete_with_meta(
+        &self,
+        id: &ResponseIdRef,
+    ) -> Result<WithMeta<DeletionStatus<ResponseId>>, OpenAIError> {
+        self.runtime.block_on(self.inner.delete_with_meta(id))
+    }
+
+    /// See [`ResponsesHandler::cancel`](crate::responses::ResponsesHandler::cancel).
+    pub fn cancel(&self, id: &ResponseIdRef) -> Result<Response, OpenAIError> {
+        self.runtime.block_on(self.inner.cancel(id))
+    }
+
+    /// Like [`Self::cancel`], but also returns the response metadata.
+    pub fn cancel_with_meta(&self, id: &ResponseIdRef) -> Result<WithMeta<Response>, OpenAIError> {
+        self.runtime.block_on(self.inner.cancel_with_meta(id))
+    }
+}
+
+#[cfg(feature = "responses-streaming")]
+mod streaming {
+    use serde_bool::True;
+
+    use crate::{
+        OpenAIError,
+        blocking::{EventIter, responses::ResponsesHandler},
+        models::responses::{ResponseIdRef, streaming::ResponseEvent},
+        responses::ResponseParams,
+        transport::streaming::StreamingTransport,
+    };
+
+    impl<T: StreamingTransport> ResponsesHandler<'_, T, True> {
+        /// Creates a model response and iterates over its events as they are generated.
+        pub fn create(
+            &self,
+            params: &ResponseParams<True>,
+        ) -> Result<EventIter<ResponseEvent>, OpenAIError> {
+            let stream = self.runtime.block_on(self.inner.create(params))?;
+            Ok(EventIter::new(stream, self.runtime.clone()))
+        }
+
+        /// Iterates over the events of a model response with the given ID.
+        pub fn get(&self, id: &ResponseIdRef) -> Result<EventIter<ResponseEvent>, OpenAIError> {
+            let stream = self.runtime.block_on(self.inner.get(id))?;
+            Ok(EventIter::new(stream, self.runtime.clone()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use http::{HeaderName, Method, StatusCode};
+    use serde_bool::False;
+    use serde_json::json;
+
+    use crate::{
+        OpenAIError, blocking::OpenAI, models::responses::ResponseInput,
+        models::responses::ResponseStatus, responses::ResponseParams,
+        transport::mock::MockTransport,
+    };
+
+    #[test]
+    fn test_mock_create_get_cancel() -> Result<(), OpenAIError> {
+        let response = |status| json!({"id": "resp_123", "created_at": 1741476542, "status": status, "model": "gpt-4o"});
+
+        let mock = MockTransport::new();
+        mock.expect(Method::POST, "/v1/responses")
+            .with_json_body(json!({"input": "Hello", "stream": false}))
+            .reply_header(HeaderName::from_static("x-request-id"), "req_123")
+            .respond_json(StatusCode::OK, response("queued"));
+        mock.expect(Method::GET, "/v1/responses/resp_123")
+            .respond_json(StatusCode::OK, response("in_progress"));
+        mock.expect(Method::POST, "/v1/responses/resp_123/cancel")
+            .respond_json(StatusCode::OK, response("cancelled"));
+        mock.expect(Method::GET, "/v1/responses/resp_456").respond_json(
+            StatusCode::NOT_FOUND,
+            json!({"error": {"message": "Response not found", "type": "invalid_request_error", "param": null, "code": null}}),
+        );
+
+        let client = OpenAI::new(mock.clone())?;
+        let responses = client.responses::<False>();
+
+        let params = ResponseParams {
+            input: Some(ResponseInput::Text("Hello".to_string())),
+            ..Default::default()
+        };
+        let created = responses.create_with_meta(&params)?;
+        assert_eq!(created.data.id.0, "resp_123");
+        assert_eq!(created.meta.request_id.as_deref(), Some("req_123"));
+
+        let response = responses.get("resp_123".into())?;
+        assert_eq!(response.status, Some(ResponseStatus::InProgress));
+
+        let response = responses.cancel("resp_123".into())?;
+        assert_eq!(response.status, Some(ResponseStatus::Cancelled));
+
+        let err = responses.get("resp_456".into()).unwrap_err();
+        assert_eq!(err.api_error().unwrap().message, "Response not found");
+
+        mock.assert_done();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_delete() -> Result<(), OpenAIError> {
+        let mock = MockTransport::new();
+        mock.expect(Method::DELETE, "/v1/responses/resp_123")
+            .respond_json(
+                StatusCode::OK,
+                json!({"id": "resp_123", "object": "response", "deleted": true}),
+            );
+
+        let client = OpenAI::new(mock.clone())?;
+
+        let deleted = client.responses::<False>().delete("resp_123".into())?;
+        assert!(deleted.deleted);
+
+        mock.assert_done();
+
+        Ok(())
+    }
+}
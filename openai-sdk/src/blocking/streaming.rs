@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use tokio::runtime::Runtime;
+
+use crate::{
+    models::responses::{Response, streaming::ResponseEvent},
+    responses::accumulator::ResponseStreamError,
+    transport::{
+        meta::ResponseMeta,
+        streaming::{OpenAIStreamingError, ParsedEventStream},
+    },
+};
+
+/// Blocking iterator over the events of a [`ParsedEventStream`], waiting for each event on the calling thread.
+pub struct EventIter<E> {
+    stream: ParsedEventStream<E>,
+    runtime: Arc<Runtime>,
+}
+
+impl<E> EventIter<E> {
+    pub(crate) fn new(stream: ParsedEventStream<E>, runtime: Arc<Runtime>) -> Self {
+        Self { stream, runtime }
+    }
+
+    /// Returns the metadata of the response the events are read from.
+    pub fn meta(&self) -> &ResponseMeta {
+        self.stream.meta()
+    }
+
+    /// Returns the underlying stream. It can only be polled by a runtime with a time driver if an idle timeout is set.
+    pub fn into_stream(self) -> ParsedEventStream<E> {
+        self.stream
+    }
+}
+
+impl EventIter<ResponseEvent> {
+    /// Waits until the response is completed and returns it. See [`ParsedEventStream::collect_response`].
+    pub fn collect_response(self) -> Result<Response, ResponseStreamError> {
+        self.runtime.block_on(self.stream.collect_response())
+    }
+}
+
+impl<E> Iterator for EventIter<E>
+where
+    E: DeserializeOwned + Unpin,
+{
+    type Item = Result<E, OpenAIStreamingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use http::{Method, StatusCode};
+    use serde_bool::True;
+    use serde_json::{Value, json};
+
+    use crate::{
+        blocking::OpenAI,
+        models::responses::{ResponseStatus, streaming::ResponseEvent},
+        responses::{ResponseParams, accumulator::ResponseStreamError},
+        transport::{mock::MockTransport, streaming::OpenAIStreamingError},
+    };
+
+    fn response(status: &str, output: Value) -> Value {
+        json!({"id": "resp_123", "created_at": 1741476542, "status": status, "model": "gpt-4o", "output": output})
+    }
+
+    fn sse(events: &[Value]) -> String {
+        events
+            .iter()
+            .map(|event| {
+                format!(
+                    "event: {}\ndata: {event}\n\n",
+                    event["type"].as_str().unwrap()
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_mock_stream() {
+        let events = [
+            json!({"type": "response.created", "sequence_number": 0, "response": response("in_progress", json!([]))}),
+            json!({"type": "response.output_item.added", "sequence_number": 1, "output_index": 0, "item": {"id": "msg_123", "type": "message", "status": "in_progress", "role": "assistant", "content": []}}),
+            json!({"type": "response.content_part.added", "sequence_number": 2, "item_id": "msg_123", "output_index": 0, "content_index": 0, "part": {"type": "output_text", "text": "", "annotations": []}}),
+            json!({"type": "response.output_text.delta", "sequence_number": 3, "item_id": "msg_123", "output_index": 0, "content_index": 0, "delta": "Hi"}),
+            json!({
+                "type": "response.completed",
+                "sequence_number": 4,
+                "response": response("completed", json!([
+                    {"id": "msg_123", "type": "message", "status": "completed", "role": "assistant", "content": [{"type": "output_text", "text": "Hi", "annotations": []}]},
+                ])),
+            }),
+        ];
+
+        let mock = MockTransport::new();
+        mock.expect(Method::POST, "/v1/responses")
+            .with_json_body(json!({"stream": true}))
+            .times(2)
+            .respond_sse(StatusCode::OK, sse(&events));
+        mock.expect(Method::GET, "/v1/responses/resp_123")
+            .with_query("stream", "true")
+            .respond_sse(StatusCode::OK, sse(&events[..4]));
+
+        let client = OpenAI::new(mock.clone()).unwrap();
+        let responses = client.responses::<True>();
+
+        let events: Vec<ResponseEvent> = responses
+            .create(&ResponseParams::default())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(events.len(), 5);
+        assert!(
+            matches!(events[3], ResponseEvent::OutputTextDelta { ref delta, .. } if delta == "Hi")
+        );
+        assert!(matches!(events[4], ResponseEvent::Completed { .. }));
+
+        let response = responses
+            .create(&ResponseParams::default())
+            .unwrap()
+            .collect_response()
+            .unwrap();
+        assert_eq!(response.status, Some(ResponseStatus::Completed));
+        assert_eq!(response.output_text(), "Hi");
+
+        assert!(matches!(
+            responses.get("resp_123".into()).unwrap().collect_response(),
+            Err(ResponseStreamError::Stream {
+                source: OpenAIStreamingError::UnexpectedEnd
+            })
+        ));
+
+        mock.assert_done();
+    }
+}
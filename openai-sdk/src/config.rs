@@ -0,0 +1,250 @@
+use std::str::FromStr;
+#[cfg(feature = "reqwest")]
+use std::time::Duration;
+
+use secrecy::SecretString;
+use serde::Deserialize;
+use url::Url;
+
+use crate::OpenAIError;
+#[cfg(feature = "reqwest")]
+use crate::transport::{StandardHttpTransport, StandardHttpTransportBuilder, retry::RetryPolicy};
+
+pub(crate) const API_KEY_ENV: &str = "OPENAI_API_KEY";
+const BASE_URL_ENV: &str = "OPENAI_BASE_URL";
+const ORGANIZATION_ENV: &str = "OPENAI_ORG_ID";
+const PROJECT_ENV: &str = "OPENAI_PROJECT_ID";
+const TIMEOUT_ENV: &str = "OPENAI_TIMEOUT";
+const MAX_RETRIES_ENV: &str = "OPENAI_MAX_RETRIES";
+
+/// Settings of a [`StandardHttpTransport`], meant to be embedded in the configuration file of an application.
+///
+/// Every field is optional. A missing `api_key` is read from `OPENAI_API_KEY` when building the transport, so the key can be kept out of the file.
+///
+/// ```toml
+/// [openai]
+/// base_url = "https://gateway.internal/openai/"
+/// project = "proj_123"
+/// timeout_secs = 30
+/// max_retries = 2
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientConfig {
+    pub api_key: Option<SecretString>,
+    /// See [`StandardHttpTransportBuilder::base_url`].
+    pub base_url: Option<Url>,
+    /// Sent as the `OpenAI-Organization` header.
+    pub organization: Option<String>,
+    /// Sent as the `OpenAI-Project` header.
+    pub project: Option<String>,
+    /// Total timeout of a request in seconds. See [`RequestOptions::timeout`](crate::transport::RequestOptions::timeout).
+    pub timeout_secs: Option<f64>,
+    /// Timeout for establishing connections in seconds.
+    pub connect_timeout_secs: Option<f64>,
+    /// Longest pause between server-sent events in seconds.
+    pub stream_idle_timeout_secs: Option<f64>,
+    /// Number of retries after the first attempt. Uses the delays of [`RetryPolicy::default`].
+    pub max_retries: Option<u32>,
+}
+
+impl ClientConfig {
+    /// Reads `OPENAI_API_KEY`, `OPENAI_BASE_URL`, `OPENAI_ORG_ID`, `OPENAI_PROJECT_ID`, `OPENAI_TIMEOUT` (in seconds) and `OPENAI_MAX_RETRIES`. Unset variables are left empty.
+    pub fn from_env() -> Result<Self, OpenAIError> {
+        Self::from_lookup(env_var)
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, OpenAIError> {
+        Ok(Self {
+            api_key: lookup(API_KEY_ENV).map(Into::into),
+            base_url: parse_var(&lookup, BASE_URL_ENV, "a URL")?,
+            organization: lookup(ORGANIZATION_ENV),
+            project: lookup(PROJECT_ENV),
+            timeout_secs: parse_var(&lookup, TIMEOUT_ENV, "a number of seconds")?,
+            connect_timeout_secs: None,
+            stream_idle_timeout_secs: None,
+            max_retries: parse_var(&lookup, MAX_RETRIES_ENV, "a number of retries")?,
+        })
+    }
+
+    /// Creates a transport builder with these settings. Fails if the API key is neither configured nor set in `OPENAI_API_KEY`, or if a timeout is invalid.
+    #[cfg(feature = "reqwest")]
+    pub fn builder(&self) -> Result<StandardHttpTransportBuilder, OpenAIError> {
+        self.builder_with_lookup(env_var)
+    }
+
+    #[cfg(feature = "reqwest")]
+    fn builder_with_lookup(
+        &self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<StandardHttpTransportBuilder, OpenAIError> {
+        let api_key = match &self.api_key {
+            Some(api_key) => api_key.clone(),
+            None => lookup(API_KEY_ENV)
+                .ok_or_else(|| OpenAIError::Config {
+                    name: "api_key",
+                    message: format!(
+                        "not set in the config or the {API_KEY_ENV} environment variable"
+                    ),
+                })?
+                .into(),
+        };
+
+        let mut builder = StandardHttpTransport::builder(api_key);
+
+        if let Some(base_url) = &self.base_url {
+            builder = builder.base_url(base_url.clone());
+        }
+
+        if let Some(organization) = &self.organization {
+            builder = builder.organization(organization);
+        }
+
+        if let Some(project) = &self.project {
+            builder = builder.project(project);
+        }
+
+        if let Some(timeout) = duration("timeout_secs", self.timeout_secs)? {
+            builder = builder.timeout(timeout);
+        }
+
+        if let Some(timeout) = duration("connect_timeout_secs", self.connect_timeout_secs)? {
+            builder = builder.connect_timeout(timeout);
+        }
+
+        if let Some(timeout) = duration("stream_idle_timeout_secs", self.stream_idle_timeout_secs)?
+        {
+            builder = builder.stream_idle_timeout(timeout);
+        }
+
+        if let Some(max_retries) = self.max_retries {
+            builder = builder.retry_policy(RetryPolicy {
+                max_attempts: max_retries.saturating_add(1),
+                ..Default::default()
+            });
+        }
+
+        Ok(builder)
+    }
+
+    #[cfg(feature = "reqwest")]
+    pub fn build(&self) -> Result<StandardHttpTransport, OpenAIError> {
+        self.builder()?.build()
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var_os(name).map(|value| value.to_string_lossy().into_owned())
+}
+
+fn parse_var<T: FromStr>(
+    lookup: impl Fn(&str) -> Option<String>,
+    name: &'static str,
+    expected: &str,
+) -> Result<Option<T>, OpenAIError> {
+    lookup(name)
+        .map(|value| {
+            value.trim().parse().map_err(|_| OpenAIError::Config {
+                name,
+                message: format!("expected {expected}, got `{value}`"),
+            })
+        })
+        .transpose()
+}
+
+#[cfg(feature = "reqwest")]
+fn duration(name: &'static str, secs: Option<f64>) -> Result<Option<Duration>, OpenAIError> {
+    secs.map(|secs| {
+        Duration::try_from_secs_f64(secs).map_err(|_| OpenAIError::Config {
+            name,
+            message: format!("expected a non-negative number of seconds, got {secs}"),
+        })
+    })
+    .transpose()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use secrecy::ExposeSecret;
+    #[cfg(feature = "reqwest")]
+    use serde_json::json;
+
+    #[cfg(feature = "reqwest")]
+    use crate::OpenAIError;
+    use crate::config::ClientConfig;
+
+    #[test]
+    fn test_from_lookup() {
+        let env = HashMap::from([
+            ("OPENAI_API_KEY", "sk-test"),
+            ("OPENAI_BASE_URL", "https://gateway.internal/openai/"),
+            ("OPENAI_PROJECT_ID", "proj_123"),
+            ("OPENAI_TIMEOUT", "2.5"),
+        ]);
+
+        let config =
+            ClientConfig::from_lookup(|name| env.get(name).map(ToString::to_string)).unwrap();
+        assert_eq!(config.api_key.unwrap().expose_secret(), "sk-test");
+        assert_eq!(
+            config.base_url.unwrap().as_str(),
+            "https://gateway.internal/openai/"
+        );
+        assert_eq!(config.organization, None);
+        assert_eq!(config.project.as_deref(), Some("proj_123"));
+        assert_eq!(config.timeout_secs, Some(2.5));
+
+        let err = ClientConfig::from_lookup(|name| {
+            (name == "OPENAI_MAX_RETRIES").then(|| "many".to_string())
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid configuration of OPENAI_MAX_RETRIES: expected a number of retries, got `many`"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "reqwest")]
+    fn test_builder() {
+        let config: ClientConfig = serde_json::from_value(json!({
+            "base_url": "https://gateway.internal/openai",
+            "timeout_secs": 30,
+            "max_retries": 0,
+        }))
+        .unwrap();
+
+        assert!(matches!(
+            config.builder_with_lookup(|_| None),
+            Err(OpenAIError::Config {
+                name: "api_key",
+                ..
+            })
+        ));
+
+        let transport = config
+            .builder_with_lookup(|_| Some("sk-test".to_string()))
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            transport.base_url().as_str(),
+            "https://gateway.internal/openai/"
+        );
+
+        let config = ClientConfig {
+            timeout_secs: Some(-1.0),
+            ..config
+        };
+        assert!(matches!(
+            config.builder_with_lookup(|_| Some("sk-test".to_string())),
+            Err(OpenAIError::Config {
+                name: "timeout_secs",
+                ..
+            })
+        ));
+
+        assert!(serde_json::from_value::<ClientConfig>(json!({ "timeout": 30 })).is_err());
+    }
+}
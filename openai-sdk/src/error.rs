@@ -0,0 +1,174 @@
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+/// Error returned by the API, parsed from the `{"error": {...}}` envelope.
+///
+/// https://platform.openai.com/docs/guides/error-codes
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ApiError {
+    /// A human-readable description of the error.
+    pub message: String,
+    /// The error category, e.g. `invalid_request_error`.
+    #[serde(rename = "type")]
+    pub r#type: Option<String>,
+    /// The request parameter the error relates to, if any.
+    pub param: Option<String>,
+    /// A machine-readable error code.
+    pub code: Option<ApiErrorCode>,
+}
+
+impl ApiError {
+    /// Parses the error envelope from a response body.
+    pub fn from_body(text: &str) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct Envelope {
+            error: ApiError,
+        }
+
+        serde_json::from_str::<Envelope>(text)
+            .ok()
+            .map(|envelope| envelope.error)
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        self.code == Some(ApiErrorCode::RateLimitExceeded)
+    }
+
+    pub fn is_quota_exceeded(&self) -> bool {
+        self.code == Some(ApiErrorCode::InsufficientQuota)
+    }
+}
+
+/// Well-known values of [`ApiError::code`]. Codes not listed here are kept in [`ApiErrorCode::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Display, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum ApiErrorCode {
+    #[display("invalid_api_key")]
+    InvalidApiKey,
+    #[display("insufficient_quota")]
+    InsufficientQuota,
+    #[display("rate_limit_exceeded")]
+    RateLimitExceeded,
+    #[display("context_length_exceeded")]
+    ContextLengthExceeded,
+    #[display("model_not_found")]
+    ModelNotFound,
+    #[display("unsupported_parameter")]
+    UnsupportedParameter,
+    #[display("unsupported_value")]
+    UnsupportedValue,
+    #[display("invalid_value")]
+    InvalidValue,
+    #[display("missing_required_parameter")]
+    MissingRequiredParameter,
+    #[display("invalid_prompt")]
+    InvalidPrompt,
+    #[display("server_error")]
+    ServerError,
+    #[display("{_0}")]
+    Other(String),
+}
+
+impl From<String> for ApiErrorCode {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "invalid_api_key" => Self::InvalidApiKey,
+            "insufficient_quota" => Self::InsufficientQuota,
+            "rate_limit_exceeded" => Self::RateLimitExceeded,
+            "context_length_exceeded" => Self::ContextLengthExceeded,
+            "model_not_found" => Self::ModelNotFound,
+            "unsupported_parameter" => Self::UnsupportedParameter,
+            "unsupported_value" => Self::UnsupportedValue,
+            "invalid_value" => Self::InvalidValue,
+            "missing_required_parameter" => Self::MissingRequiredParameter,
+            "invalid_prompt" => Self::InvalidPrompt,
+            "server_error" => Self::ServerError,
+            _ => Self::Other(value),
+        }
+    }
+}
+
+impl From<ApiErrorCode> for String {
+    fn from(value: ApiErrorCode) -> Self {
+        match value {
+            ApiErrorCode::Other(code) => code,
+            code => code.to_string(),
+        }
+    }
+}
+
+/// Which limit was exceeded by an [`OpenAIError::Timeout`](crate::OpenAIError::Timeout).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum TimeoutKind {
+    /// Establishing the connection took longer than the connect timeout.
+    #[display("connect")]
+    Connect,
+    /// The request, including reading the response body, took longer than the total timeout.
+    #[display("request")]
+    Request,
+}
+
+#[cfg(test)]
+mod test {
+    use http::StatusCode;
+
+    use crate::{
+        OpenAIError,
+        error::{ApiError, ApiErrorCode, TimeoutKind},
+        transport::meta::ResponseMeta,
+    };
+
+    #[test]
+    fn test_from_body() {
+        let error = ApiError::from_body(
+            r#"{"error": {"message": "Rate limit reached", "type": "requests", "param": null, "code": "rate_limit_exceeded"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(error.message, "Rate limit reached");
+        assert_eq!(error.r#type.as_deref(), Some("requests"));
+        assert_eq!(error.param, None);
+        assert_eq!(error.code, Some(ApiErrorCode::RateLimitExceeded));
+        assert!(error.is_rate_limited());
+
+        let error = ApiError::from_body(r#"{"error": {"message": "?", "code": "brand_new_code"}}"#)
+            .unwrap();
+        assert_eq!(
+            error.code,
+            Some(ApiErrorCode::Other("brand_new_code".to_string()))
+        );
+
+        assert_eq!(ApiError::from_body("<html>Bad Gateway</html>"), None);
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let api = |status, text: &str| {
+            OpenAIError::api(
+                text.to_string(),
+                ResponseMeta::new(status, Default::default()),
+            )
+        };
+
+        let rate_limited = api(
+            StatusCode::TOO_MANY_REQUESTS,
+            r#"{"error": {"message": "", "code": "rate_limit_exceeded"}}"#,
+        );
+        assert!(rate_limited.is_retryable());
+        assert!(rate_limited.is_rate_limited());
+
+        let quota = api(
+            StatusCode::TOO_MANY_REQUESTS,
+            r#"{"error": {"message": "", "code": "insufficient_quota"}}"#,
+        );
+        assert!(!quota.is_retryable());
+        assert!(!quota.is_rate_limited());
+
+        assert!(api(StatusCode::BAD_GATEWAY, "<html>Bad Gateway</html>").is_retryable());
+        assert!(!api(StatusCode::BAD_REQUEST, "").is_retryable());
+
+        let timeout = |kind| OpenAIError::Timeout { kind, meta: None };
+        assert!(timeout(TimeoutKind::Connect).is_retryable());
+        assert!(!timeout(TimeoutKind::Request).is_retryable());
+    }
+}
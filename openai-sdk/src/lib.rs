@@ -1,18 +1,43 @@
-use reqwest::StatusCode;
+use http::StatusCode;
+#[cfg(feature = "reqwest")]
 use secrecy::SecretString;
 use snafu::Snafu;
+#[cfg(feature = "reqwest")]
+use url::Url;
 
-use crate::transport::StandardHttpTransport;
+#[cfg(feature = "reqwest")]
+use crate::{config::ClientConfig, transport::StandardHttpTransport};
+use crate::{
+    error::{ApiError, TimeoutKind},
+    transport::{
+        BoxError,
+        interceptor::{Intercepted, Interceptor},
+        meta::ResponseMeta,
+        rate_limit::{RateLimited, RateLimiter},
+    },
+};
 
 #[cfg(feature = "responses")]
 pub mod responses;
 
 pub mod transport;
 
+pub mod config;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+pub mod error;
+
 pub mod models;
 
 mod macros;
 
+// The HTTP spans are only recorded by the reqwest and tower transports.
+#[cfg(feature = "tracing")]
+#[cfg_attr(not(any(feature = "reqwest", feature = "tower")), allow(dead_code))]
+mod telemetry;
+
 #[cfg(feature = "responses")]
 mod generated {
     openai_sdk_codegen::generate!("../openapi.documented.yaml");
@@ -22,35 +47,242 @@ mod generated {
 pub enum OpenAIError {
     #[snafu(transparent)]
     UrlParse { source: url::ParseError },
-    #[snafu(transparent)]
-    Reqwest { source: reqwest::Error },
+    #[snafu(display("Could not read environment variable {name}: {source}"))]
+    Env {
+        source: std::env::VarError,
+        name: &'static str,
+    },
+    /// An error of the reqwest client. `meta` is set if the error occurred while reading the response body.
+    #[cfg(feature = "reqwest")]
+    #[snafu(display("{source}"))]
+    Reqwest {
+        source: reqwest::Error,
+        meta: Option<Box<ResponseMeta>>,
+    },
+    /// An error of a custom HTTP stack, such as a tower service. `meta` is set if the error occurred while reading the response body.
+    #[snafu(display("Transport error: {source}"))]
+    Transport {
+        source: BoxError,
+        meta: Option<Box<ResponseMeta>>,
+    },
+    /// The credential provider of the transport failed.
+    #[snafu(display("Could not obtain credentials: {source}"))]
+    Credentials { source: BoxError },
+    /// A configuration value is missing or invalid.
+    #[snafu(display("Invalid configuration of {name}: {message}"))]
+    Config { name: &'static str, message: String },
+    /// The request timed out. `meta` is set if the timeout elapsed while reading the response body.
+    #[snafu(display("Request timed out ({kind})"))]
+    Timeout {
+        kind: TimeoutKind,
+        meta: Option<Box<ResponseMeta>>,
+    },
+    #[snafu(display("Could not serialize request: {source}"))]
+    SerializeRequest { source: serde_json::Error },
+    #[snafu(display("Could not serialize query: {source}"))]
+    SerializeQuery {
+        source: serde_urlencoded::ser::Error,
+    },
     #[snafu(display("Could not deserialize response: {source}"))]
     DeserializeResponse {
         source: serde_json::Error,
         text: String,
+        meta: Box<ResponseMeta>,
+    },
+    /// A streaming request was answered with a body that is not an event stream, e.g. because the endpoint ignored the `stream` parameter.
+    #[snafu(display("Expected an event stream, got {}", content_type.as_deref().unwrap_or("no content type")))]
+    UnexpectedContentType {
+        content_type: Option<String>,
+        text: String,
+        meta: Box<ResponseMeta>,
+    },
+    #[snafu(display("ApiError ({status}): {}", error.as_ref().map_or(text, |error| &error.message)))]
+    Api {
+        status: StatusCode,
+        /// The parsed error envelope, if the body contained one.
+        error: Option<Box<ApiError>>,
+        text: String,
+        meta: Box<ResponseMeta>,
     },
-    #[snafu(display("ApiError ({status}): {text}"))]
-    Api { status: StatusCode, text: String },
 }
 
+impl OpenAIError {
+    #[cfg_attr(not(any(feature = "reqwest", feature = "tower")), allow(dead_code))]
+    pub(crate) fn api(text: String, meta: ResponseMeta) -> Self {
+        Self::Api {
+            status: meta.status,
+            error: ApiError::from_body(&text).map(Box::new),
+            text,
+            meta: Box::new(meta),
+        }
+    }
+
+    /// Attaches the metadata of the response whose body could not be read to transport errors and timeouts.
+    #[cfg_attr(not(any(feature = "reqwest", feature = "tower")), allow(dead_code))]
+    pub(crate) fn with_meta(mut self, response_meta: &ResponseMeta) -> Self {
+        match &mut self {
+            #[cfg(feature = "reqwest")]
+            Self::Reqwest { meta, .. } => *meta = Some(Box::new(response_meta.clone())),
+            Self::Transport { meta, .. } | Self::Timeout { meta, .. } => {
+                *meta = Some(Box::new(response_meta.clone()));
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Returns the metadata of the response that caused the error.
+    ///
+    /// Only errors raised once a response was received have metadata: API errors, undeserializable or unexpected bodies, and transport errors or timeouts while reading the body. Errors raised before, such as connection errors, have none.
+    pub fn meta(&self) -> Option<&ResponseMeta> {
+        match self {
+            Self::DeserializeResponse { meta, .. }
+            | Self::UnexpectedContentType { meta, .. }
+            | Self::Api { meta, .. } => Some(meta),
+            #[cfg(feature = "reqwest")]
+            Self::Reqwest { meta, .. } => meta.as_deref(),
+            Self::Transport { meta, .. } | Self::Timeout { meta, .. } => meta.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the `x-request-id` of the response that caused the error, if any. See [`Self::meta`] for the errors that have one.
+    pub fn request_id(&self) -> Option<&str> {
+        self.meta()?.request_id.as_deref()
+    }
+
+    /// Returns the parsed API error, if this is an API error with a recognizable body.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Self::Api { error, .. } => error.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether the request is rate limited and can succeed later. Exhausted quota is not considered rate limiting.
+    pub fn is_rate_limited(&self) -> bool {
+        match self {
+            Self::Api { status, error, .. } => {
+                *status == StatusCode::TOO_MANY_REQUESTS
+                    && !error.as_deref().is_some_and(ApiError::is_quota_exceeded)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether retrying the same request may succeed: connection errors, connect timeouts, rate limits, lock conflicts and server errors. Request timeouts are not retried, because the server may already have processed a request that is not idempotent.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            #[cfg(feature = "reqwest")]
+            Self::Reqwest { source, .. } => source.is_connect(),
+            Self::Timeout { kind, .. } => *kind == TimeoutKind::Connect,
+            Self::Api { status, .. } => {
+                matches!(*status, StatusCode::REQUEST_TIMEOUT | StatusCode::CONFLICT)
+                    || status.is_server_error()
+                    || self.is_rate_limited()
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl From<reqwest::Error> for OpenAIError {
+    fn from(source: reqwest::Error) -> Self {
+        if !source.is_timeout() {
+            Self::Reqwest { source, meta: None }
+        } else if source.is_connect() {
+            Self::Timeout {
+                kind: TimeoutKind::Connect,
+                meta: None,
+            }
+        } else {
+            Self::Timeout {
+                kind: TimeoutKind::Request,
+                meta: None,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "reqwest")]
 #[derive(Clone)]
 pub struct OpenAI<T = StandardHttpTransport> {
     transport: T,
 }
 
+/// Without the `reqwest` feature there is no default transport; pass one such as `HyperTransport` to [`OpenAI::new`].
+#[cfg(not(feature = "reqwest"))]
+#[derive(Clone)]
+pub struct OpenAI<T> {
+    transport: T,
+}
+
+#[cfg(feature = "reqwest")]
 impl OpenAI<StandardHttpTransport> {
+    /// Creates a client configured from the environment, see [`ClientConfig::from_env`].
+    pub fn from_env() -> Result<Self, OpenAIError> {
+        Self::from_config(&ClientConfig::from_env()?)
+    }
+
+    /// Creates a client from a [`ClientConfig`], e.g. a section of the application's configuration file.
+    pub fn from_config(config: &ClientConfig) -> Result<Self, OpenAIError> {
+        Ok(Self {
+            transport: config.build()?,
+        })
+    }
+
     pub fn standard_http(access_token: SecretString, client: reqwest::Client) -> Self {
         Self {
             transport: StandardHttpTransport::new(access_token, client),
         }
     }
+
+    pub fn standard_http_with_base_url(
+        access_token: SecretString,
+        client: reqwest::Client,
+        base_url: Url,
+    ) -> Result<Self, OpenAIError> {
+        Ok(Self {
+            transport: StandardHttpTransport::builder(access_token)
+                .client(client)
+                .base_url(base_url)
+                .build()?,
+        })
+    }
 }
 
 impl<T> OpenAI<T> {
+    pub fn new(transport: T) -> Self {
+        Self { transport }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Wraps the transport in an interceptor. Interceptors added later run their `before_send` hook first and see the response last.
+    pub fn with_interceptor(
+        self,
+        interceptor: impl Interceptor + 'static,
+    ) -> OpenAI<Intercepted<T>> {
+        OpenAI {
+            transport: Intercepted::new(self.transport).with(interceptor),
+        }
+    }
+
+    /// Wraps the transport in a rate limiter. Pass clones of the same limiter to share a budget between clients.
+    pub fn with_rate_limiter(self, limiter: RateLimiter) -> OpenAI<RateLimited<T>> {
+        OpenAI {
+            transport: RateLimited::new(self.transport, limiter),
+        }
+    }
+
     #[cfg(feature = "responses")]
     pub fn responses<Stream>(&self) -> responses::ResponsesHandler<'_, T, Stream> {
         responses::ResponsesHandler {
             client: self,
+            options: Default::default(),
             _marker: std::marker::PhantomData,
         }
     }
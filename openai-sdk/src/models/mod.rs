@@ -1,2 +1,15 @@
+use serde::{Deserialize, Serialize};
+
 #[cfg(feature = "responses")]
 pub mod responses;
+
+/// Confirmation returned by endpoints that delete an object.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DeletionStatus<Id = String> {
+    /// The ID of the deleted object.
+    pub id: Id,
+    /// The object type of the deleted object, e.g. `response`.
+    pub object: String,
+    /// Whether the object was deleted.
+    pub deleted: bool,
+}
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use derive_more::From;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use serde_with::serde_as;
 
 use crate::define_ids;
@@ -20,6 +21,302 @@ pub struct Response {
     pub created_at: DateTime<Utc>,
     /// Unique identifier for this Response.
     pub id: ResponseId,
+    /// Model ID used to generate the response, like `gpt-4o` or `o3`. OpenAI offers a wide range of models with different capabilities, performance characteristics, and price points. Refer to the [model guide](https://platform.openai.com/docs/models) to browse and compare available models.
+    pub model: String,
+    /// The status of the response generation.
+    pub status: Option<ResponseStatus>,
+    /// An array of content items generated by the model.
+    #[serde(default)]
+    pub output: Vec<ResponseOutputItem>,
+    /// An error object returned when the model fails to generate a Response.
+    pub error: Option<ResponseError>,
+    /// Details about why the response is incomplete.
+    pub incomplete_details: Option<IncompleteDetails>,
+    /// Represents token usage details including input tokens, output tokens, a breakdown of output tokens, and the total tokens used.
+    pub usage: Option<ResponseUsage>,
+}
+
+impl Response {
+    /// Concatenates the text outputs of all output messages, ignoring refusals and other items.
+    pub fn output_text(&self) -> String {
+        self.output
+            .iter()
+            .filter_map(|item| match item {
+                ResponseOutputItem::Message(message) => Some(&message.content),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|content| match content {
+                OutputContent::OutputText(output) => Some(output.text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseStatus {
+    Completed,
+    Failed,
+    InProgress,
+    Cancelled,
+    Queued,
+    Incomplete,
+}
+
+/// An error object returned when the model fails to generate a Response.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResponseError {
+    /// The error code for the response, e.g. `server_error` or `rate_limit_exceeded`.
+    pub code: String,
+    /// A human-readable description of the error.
+    pub message: String,
+}
+
+/// Details about why the response is incomplete.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IncompleteDetails {
+    /// The reason why the response is incomplete, e.g. `max_output_tokens` or `content_filter`.
+    pub reason: Option<String>,
+}
+
+/// An output item generated by the model.
+///
+/// Items of types this crate doesn't model yet, such as built-in tool calls, are kept as [`ResponseOutputItem::Unknown`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum ResponseOutputItem {
+    /// An output message from the model.
+    #[serde(rename = "message")]
+    Message(OutputMessage),
+    /// A tool call to run a function. See the [function calling guide](https://platform.openai.com/docs/guides/function-calling) for more information.
+    #[serde(rename = "function_call")]
+    FunctionCall(FunctionToolCall),
+    /// A description of the chain of thought used by a reasoning model while generating a response.
+    #[serde(rename = "reasoning")]
+    Reasoning(ReasoningItem),
+    /// The raw JSON of an item of another type.
+    #[serde(untagged)]
+    Unknown(Value),
+}
+
+impl ResponseOutputItem {
+    /// The unique ID of the item, if it has one.
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Self::Message(message) => Some(&message.id),
+            Self::FunctionCall(call) => call.id.as_deref(),
+            Self::Reasoning(reasoning) => Some(&reasoning.id),
+            Self::Unknown(value) => value.get("id").and_then(Value::as_str),
+        }
+    }
+}
+
+/// An output message from the model.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OutputMessage {
+    /// The unique ID of the output message.
+    pub id: String,
+    /// The role of the output message. Always `assistant`.
+    pub role: String,
+    /// The status of the message input.
+    pub status: ItemStatus,
+    /// The content of the output message.
+    pub content: Vec<OutputContent>,
+}
+
+/// The status of an output item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+    InProgress,
+    Completed,
+    Incomplete,
+}
+
+/// A content part of an output message.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum OutputContent {
+    /// A text output from the model.
+    #[serde(rename = "output_text")]
+    OutputText(OutputText),
+    /// A refusal from the model.
+    #[serde(rename = "refusal")]
+    Refusal {
+        /// The refusal explanation from the model.
+        refusal: String,
+    },
+    /// The raw JSON of a content part of another type.
+    #[serde(untagged)]
+    Unknown(Value),
+}
+
+/// A text output from the model.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct OutputText {
+    /// The text output from the model.
+    pub text: String,
+    /// The annotations of the text output.
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+    /// The log probabilities of the output tokens, if requested with `include`.
+    pub logprobs: Option<Vec<LogProb>>,
+}
+
+/// An annotation of a text output.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum Annotation {
+    /// A citation to a file.
+    #[serde(rename = "file_citation")]
+    FileCitation {
+        /// The ID of the file.
+        file_id: String,
+        /// The filename of the file cited.
+        filename: Option<String>,
+        /// The index of the file in the list of files.
+        index: u64,
+    },
+    /// A citation for a web resource used to generate a model response.
+    #[serde(rename = "url_citation")]
+    UrlCitation {
+        /// The URL of the web resource.
+        url: String,
+        /// The title of the web resource.
+        title: String,
+        /// The index of the first character of the URL citation in the message.
+        start_index: u64,
+        /// The index of the last character of the URL citation in the message.
+        end_index: u64,
+    },
+    /// A citation for a container file used to generate a model response.
+    #[serde(rename = "container_file_citation")]
+    ContainerFileCitation {
+        /// The ID of the container file.
+        container_id: String,
+        /// The ID of the file.
+        file_id: String,
+        /// The filename of the container file cited.
+        filename: String,
+        /// The index of the first character of the container file citation in the message.
+        start_index: u64,
+        /// The index of the last character of the container file citation in the message.
+        end_index: u64,
+    },
+    /// A path to a file.
+    #[serde(rename = "file_path")]
+    FilePath {
+        /// The ID of the file.
+        file_id: String,
+        /// The index of the file in the list of files.
+        index: u64,
+    },
+    /// The raw JSON of an annotation of another type.
+    #[serde(untagged)]
+    Unknown(Value),
+}
+
+/// The log probability of a token.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LogProb {
+    pub token: String,
+    pub logprob: f64,
+    #[serde(default)]
+    pub bytes: Vec<u8>,
+    /// The most likely tokens at this position.
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogProb>,
+}
+
+/// The log probability of one of the most likely tokens at a position.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TopLogProb {
+    pub token: String,
+    pub logprob: f64,
+    #[serde(default)]
+    pub bytes: Vec<u8>,
+}
+
+/// A tool call to run a function. See the [function calling guide](https://platform.openai.com/docs/guides/function-calling) for more information.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FunctionToolCall {
+    /// The unique ID of the function tool call.
+    pub id: Option<String>,
+    /// The unique ID of the function tool call generated by the model.
+    pub call_id: String,
+    /// The name of the function to run.
+    pub name: String,
+    /// A JSON string of the arguments to pass to the function.
+    pub arguments: String,
+    /// The status of the item.
+    pub status: Option<ItemStatus>,
+}
+
+/// A description of the chain of thought used by a reasoning model while generating a response.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReasoningItem {
+    /// The unique identifier of the reasoning content.
+    pub id: String,
+    /// Reasoning summary content.
+    #[serde(default)]
+    pub summary: Vec<ReasoningSummaryPart>,
+    /// Reasoning text content.
+    pub content: Option<Vec<ReasoningContent>>,
+    /// The encrypted content of the reasoning item, populated when a response is generated with `reasoning.encrypted_content` in the `include` parameter.
+    pub encrypted_content: Option<String>,
+    /// The status of the item.
+    pub status: Option<ItemStatus>,
+}
+
+/// A part of a reasoning summary.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum ReasoningSummaryPart {
+    #[serde(rename = "summary_text")]
+    SummaryText {
+        /// A summary of the reasoning output from the model so far.
+        text: String,
+    },
+}
+
+/// A part of the reasoning text.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum ReasoningContent {
+    #[serde(rename = "reasoning_text")]
+    ReasoningText {
+        /// The reasoning text from the model.
+        text: String,
+    },
+}
+
+/// Represents token usage details including input tokens, output tokens, a breakdown of output tokens, and the total tokens used.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResponseUsage {
+    /// The number of input tokens.
+    pub input_tokens: u64,
+    /// A detailed breakdown of the input tokens.
+    pub input_tokens_details: InputTokensDetails,
+    /// The number of output tokens.
+    pub output_tokens: u64,
+    /// A detailed breakdown of the output tokens.
+    pub output_tokens_details: OutputTokensDetails,
+    /// The total number of tokens used.
+    pub total_tokens: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct InputTokensDetails {
+    /// The number of tokens that were retrieved from the cache. [More on prompt caching](https://platform.openai.com/docs/guides/prompt-caching).
+    pub cached_tokens: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OutputTokensDetails {
+    /// The number of reasoning tokens.
+    pub reasoning_tokens: u64,
 }
 
 #[derive(Debug, Clone, From, Deserialize, Serialize)]
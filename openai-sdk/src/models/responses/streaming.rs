@@ -1,4 +1,657 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
+use crate::models::responses::{
+    Annotation, LogProb, OutputContent, ReasoningSummaryPart, Response, ResponseOutputItem,
+};
+
+/// An event emitted while a response is streamed.
+///
+/// https://platform.openai.com/docs/api-reference/responses-streaming
+///
+/// Events of types this crate doesn't know yet, or whose payload doesn't have the expected shape, are kept as [`ResponseEvent::Unknown`], so new server events don't break a stream.
 #[derive(Debug, Clone, Deserialize, Serialize)]
-pub enum ResponseEvent {}
+#[serde(tag = "type")]
+pub enum ResponseEvent {
+    /// An event that is emitted when a response is created.
+    #[serde(rename = "response.created")]
+    Created {
+        response: Response,
+        sequence_number: u64,
+    },
+    /// Emitted when a response is queued and waiting to be processed.
+    #[serde(rename = "response.queued")]
+    Queued {
+        response: Response,
+        sequence_number: u64,
+    },
+    /// Emitted when the response is in progress.
+    #[serde(rename = "response.in_progress")]
+    InProgress {
+        response: Response,
+        sequence_number: u64,
+    },
+    /// Emitted when the model response is complete.
+    #[serde(rename = "response.completed")]
+    Completed {
+        response: Response,
+        sequence_number: u64,
+    },
+    /// An event that is emitted when a response fails.
+    #[serde(rename = "response.failed")]
+    Failed {
+        response: Response,
+        sequence_number: u64,
+    },
+    /// An event that is emitted when a response finishes as incomplete.
+    #[serde(rename = "response.incomplete")]
+    Incomplete {
+        response: Response,
+        sequence_number: u64,
+    },
+    /// Emitted when a new output item is added.
+    #[serde(rename = "response.output_item.added")]
+    OutputItemAdded {
+        output_index: usize,
+        item: ResponseOutputItem,
+        sequence_number: u64,
+    },
+    /// Emitted when an output item is marked done.
+    #[serde(rename = "response.output_item.done")]
+    OutputItemDone {
+        output_index: usize,
+        item: ResponseOutputItem,
+        sequence_number: u64,
+    },
+    /// Emitted when a new content part is added.
+    #[serde(rename = "response.content_part.added")]
+    ContentPartAdded {
+        item_id: String,
+        output_index: usize,
+        content_index: usize,
+        part: OutputContent,
+        sequence_number: u64,
+    },
+    /// Emitted when a content part is done.
+    #[serde(rename = "response.content_part.done")]
+    ContentPartDone {
+        item_id: String,
+        output_index: usize,
+        content_index: usize,
+        part: OutputContent,
+        sequence_number: u64,
+    },
+    /// Emitted when there is an additional text delta.
+    #[serde(rename = "response.output_text.delta")]
+    OutputTextDelta {
+        item_id: String,
+        output_index: usize,
+        content_index: usize,
+        delta: String,
+        #[serde(default)]
+        logprobs: Vec<LogProb>,
+        sequence_number: u64,
+    },
+    /// Emitted when text content is finalized.
+    #[serde(rename = "response.output_text.done")]
+    OutputTextDone {
+        item_id: String,
+        output_index: usize,
+        content_index: usize,
+        text: String,
+        #[serde(default)]
+        logprobs: Vec<LogProb>,
+        sequence_number: u64,
+    },
+    /// Emitted when an annotation is added to output text content.
+    #[serde(rename = "response.output_text.annotation.added")]
+    OutputTextAnnotationAdded {
+        item_id: String,
+        output_index: usize,
+        content_index: usize,
+        annotation_index: usize,
+        annotation: Annotation,
+        sequence_number: u64,
+    },
+    /// Emitted when there is a partial refusal text.
+    #[serde(rename = "response.refusal.delta")]
+    RefusalDelta {
+        item_id: String,
+        output_index: usize,
+        content_index: usize,
+        delta: String,
+        sequence_number: u64,
+    },
+    /// Emitted when refusal text is finalized.
+    #[serde(rename = "response.refusal.done")]
+    RefusalDone {
+        item_id: String,
+        output_index: usize,
+        content_index: usize,
+        refusal: String,
+        sequence_number: u64,
+    },
+    /// Emitted when there is a partial function-call arguments delta.
+    #[serde(rename = "response.function_call_arguments.delta")]
+    FunctionCallArgumentsDelta {
+        item_id: String,
+        output_index: usize,
+        delta: String,
+        sequence_number: u64,
+    },
+    /// Emitted when function-call arguments are finalized.
+    #[serde(rename = "response.function_call_arguments.done")]
+    FunctionCallArgumentsDone {
+        item_id: String,
+        output_index: usize,
+        name: Option<String>,
+        arguments: String,
+        sequence_number: u64,
+    },
+    /// Emitted when a new reasoning summary part is added.
+    #[serde(rename = "response.reasoning_summary_part.added")]
+    ReasoningSummaryPartAdded {
+        item_id: String,
+        output_index: usize,
+        summary_index: usize,
+        part: ReasoningSummaryPart,
+        sequence_number: u64,
+    },
+    /// Emitted when a reasoning summary part is completed.
+    #[serde(rename = "response.reasoning_summary_part.done")]
+    ReasoningSummaryPartDone {
+        item_id: String,
+        output_index: usize,
+        summary_index: usize,
+        part: ReasoningSummaryPart,
+        sequence_number: u64,
+    },
+    /// Emitted when a delta is added to a reasoning summary text.
+    #[serde(rename = "response.reasoning_summary_text.delta")]
+    ReasoningSummaryTextDelta {
+        item_id: String,
+        output_index: usize,
+        summary_index: usize,
+        delta: String,
+        sequence_number: u64,
+    },
+    /// Emitted when a reasoning summary text is completed.
+    #[serde(rename = "response.reasoning_summary_text.done")]
+    ReasoningSummaryTextDone {
+        item_id: String,
+        output_index: usize,
+        summary_index: usize,
+        text: String,
+        sequence_number: u64,
+    },
+    /// Emitted when a delta is added to a reasoning text.
+    #[serde(rename = "response.reasoning_text.delta")]
+    ReasoningTextDelta {
+        item_id: String,
+        output_index: usize,
+        content_index: usize,
+        delta: String,
+        sequence_number: u64,
+    },
+    /// Emitted when a reasoning text is completed.
+    #[serde(rename = "response.reasoning_text.done")]
+    ReasoningTextDone {
+        item_id: String,
+        output_index: usize,
+        content_index: usize,
+        text: String,
+        sequence_number: u64,
+    },
+    /// Emitted when a file search call is initiated.
+    #[serde(rename = "response.file_search_call.in_progress")]
+    FileSearchCallInProgress {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when a file search is currently searching.
+    #[serde(rename = "response.file_search_call.searching")]
+    FileSearchCallSearching {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when a file search call is completed (results found).
+    #[serde(rename = "response.file_search_call.completed")]
+    FileSearchCallCompleted {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when a web search call is initiated.
+    #[serde(rename = "response.web_search_call.in_progress")]
+    WebSearchCallInProgress {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when a web search call is executing.
+    #[serde(rename = "response.web_search_call.searching")]
+    WebSearchCallSearching {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when a web search call is completed.
+    #[serde(rename = "response.web_search_call.completed")]
+    WebSearchCallCompleted {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when an image generation tool call is in progress.
+    #[serde(rename = "response.image_generation_call.in_progress")]
+    ImageGenerationCallInProgress {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when an image generation tool call is actively generating an image (intermediate state).
+    #[serde(rename = "response.image_generation_call.generating")]
+    ImageGenerationCallGenerating {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when a partial image is available during image generation streaming.
+    #[serde(rename = "response.image_generation_call.partial_image")]
+    ImageGenerationCallPartialImage {
+        item_id: String,
+        output_index: usize,
+        /// 0-based index for the partial image (backend is 1-based, but this is 0-based for the user).
+        partial_image_index: usize,
+        /// Base64-encoded partial image data, suitable for rendering as an image.
+        partial_image_b64: String,
+        sequence_number: u64,
+    },
+    /// Emitted when an image generation tool call has completed and the final image is available.
+    #[serde(rename = "response.image_generation_call.completed")]
+    ImageGenerationCallCompleted {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when a code interpreter call is in progress.
+    #[serde(rename = "response.code_interpreter_call.in_progress")]
+    CodeInterpreterCallInProgress {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when the code interpreter is actively interpreting the code snippet.
+    #[serde(rename = "response.code_interpreter_call.interpreting")]
+    CodeInterpreterCallInterpreting {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when the code interpreter call is completed.
+    #[serde(rename = "response.code_interpreter_call.completed")]
+    CodeInterpreterCallCompleted {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when a partial code snippet is streamed by the code interpreter.
+    #[serde(rename = "response.code_interpreter_call_code.delta")]
+    CodeInterpreterCallCodeDelta {
+        item_id: String,
+        output_index: usize,
+        delta: String,
+        sequence_number: u64,
+    },
+    /// Emitted when the code snippet is finalized by the code interpreter.
+    #[serde(rename = "response.code_interpreter_call_code.done")]
+    CodeInterpreterCallCodeDone {
+        item_id: String,
+        output_index: usize,
+        code: String,
+        sequence_number: u64,
+    },
+    /// Emitted when there is a delta (partial update) to the arguments of an MCP tool call.
+    #[serde(rename = "response.mcp_call_arguments.delta")]
+    McpCallArgumentsDelta {
+        item_id: String,
+        output_index: usize,
+        delta: String,
+        sequence_number: u64,
+    },
+    /// Emitted when the arguments for an MCP tool call are finalized.
+    #[serde(rename = "response.mcp_call_arguments.done")]
+    McpCallArgumentsDone {
+        item_id: String,
+        output_index: usize,
+        arguments: String,
+        sequence_number: u64,
+    },
+    /// Emitted when an MCP tool call is in progress.
+    #[serde(rename = "response.mcp_call.in_progress")]
+    McpCallInProgress {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when an MCP tool call has completed successfully.
+    #[serde(rename = "response.mcp_call.completed")]
+    McpCallCompleted {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when an MCP tool call has failed.
+    #[serde(rename = "response.mcp_call.failed")]
+    McpCallFailed {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when the system is in the process of retrieving the list of available MCP tools.
+    #[serde(rename = "response.mcp_list_tools.in_progress")]
+    McpListToolsInProgress {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when the list of available MCP tools has been successfully retrieved.
+    #[serde(rename = "response.mcp_list_tools.completed")]
+    McpListToolsCompleted {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when the attempt to list available MCP tools has failed.
+    #[serde(rename = "response.mcp_list_tools.failed")]
+    McpListToolsFailed {
+        item_id: String,
+        output_index: usize,
+        sequence_number: u64,
+    },
+    /// Emitted when there is a delta (partial update) to the input of a custom tool call.
+    #[serde(rename = "response.custom_tool_call_input.delta")]
+    CustomToolCallInputDelta {
+        item_id: String,
+        output_index: usize,
+        delta: String,
+        sequence_number: u64,
+    },
+    /// Emitted when the input for a custom tool call is finalized.
+    #[serde(rename = "response.custom_tool_call_input.done")]
+    CustomToolCallInputDone {
+        item_id: String,
+        output_index: usize,
+        input: String,
+        sequence_number: u64,
+    },
+    /// Emitted when an error occurs.
+    #[serde(rename = "error")]
+    Error {
+        /// The error code.
+        code: Option<String>,
+        /// The error message.
+        message: String,
+        /// The error parameter.
+        param: Option<String>,
+        sequence_number: u64,
+    },
+    /// The raw JSON of an event this crate doesn't know.
+    #[serde(untagged)]
+    Unknown(Value),
+}
+
+impl ResponseEvent {
+    /// The position of the event in the stream, used to resume a stream with `starting_after`. `None` for [`ResponseEvent::Unknown`] events without a `sequence_number` field.
+    pub fn sequence_number(&self) -> Option<u64> {
+        match self {
+            Self::Created {
+                sequence_number, ..
+            }
+            | Self::Queued {
+                sequence_number, ..
+            }
+            | Self::InProgress {
+                sequence_number, ..
+            }
+            | Self::Completed {
+                sequence_number, ..
+            }
+            | Self::Failed {
+                sequence_number, ..
+            }
+            | Self::Incomplete {
+                sequence_number, ..
+            }
+            | Self::OutputItemAdded {
+                sequence_number, ..
+            }
+            | Self::OutputItemDone {
+                sequence_number, ..
+            }
+            | Self::ContentPartAdded {
+                sequence_number, ..
+            }
+            | Self::ContentPartDone {
+                sequence_number, ..
+            }
+            | Self::OutputTextDelta {
+                sequence_number, ..
+            }
+            | Self::OutputTextDone {
+                sequence_number, ..
+            }
+            | Self::OutputTextAnnotationAdded {
+                sequence_number, ..
+            }
+            | Self::RefusalDelta {
+                sequence_number, ..
+            }
+            | Self::RefusalDone {
+                sequence_number, ..
+            }
+            | Self::FunctionCallArgumentsDelta {
+                sequence_number, ..
+            }
+            | Self::FunctionCallArgumentsDone {
+                sequence_number, ..
+            }
+            | Self::ReasoningSummaryPartAdded {
+                sequence_number, ..
+            }
+            | Self::ReasoningSummaryPartDone {
+                sequence_number, ..
+            }
+            | Self::ReasoningSummaryTextDelta {
+                sequence_number, ..
+            }
+            | Self::ReasoningSummaryTextDone {
+                sequence_number, ..
+            }
+            | Self::ReasoningTextDelta {
+                sequence_number, ..
+            }
+            | Self::ReasoningTextDone {
+                sequence_number, ..
+            }
+            | Self::FileSearchCallInProgress {
+                sequence_number, ..
+            }
+            | Self::FileSearchCallSearching {
+                sequence_number, ..
+            }
+            | Self::FileSearchCallCompleted {
+                sequence_number, ..
+            }
+            | Self::WebSearchCallInProgress {
+                sequence_number, ..
+            }
+            | Self::WebSearchCallSearching {
+                sequence_number, ..
+            }
+            | Self::WebSearchCallCompleted {
+                sequence_number, ..
+            }
+            | Self::ImageGenerationCallInProgress {
+                sequence_number, ..
+            }
+            | Self::ImageGenerationCallGenerating {
+                sequence_number, ..
+            }
+            | Self::ImageGenerationCallPartialImage {
+                sequence_number, ..
+            }
+            | Self::ImageGenerationCallCompleted {
+                sequence_number, ..
+            }
+            | Self::CodeInterpreterCallInProgress {
+                sequence_number, ..
+            }
+            | Self::CodeInterpreterCallInterpreting {
+                sequence_number, ..
+            }
+            | Self::CodeInterpreterCallCompleted {
+                sequence_number, ..
+            }
+            | Self::CodeInterpreterCallCodeDelta {
+                sequence_number, ..
+            }
+            | Self::CodeInterpreterCallCodeDone {
+                sequence_number, ..
+            }
+            | Self::McpCallArgumentsDelta {
+                sequence_number, ..
+            }
+            | Self::McpCallArgumentsDone {
+                sequence_number, ..
+            }
+            | Self::McpCallInProgress {
+                sequence_number, ..
+            }
+            | Self::McpCallCompleted {
+                sequence_number, ..
+            }
+            | Self::McpCallFailed {
+                sequence_number, ..
+            }
+            | Self::McpListToolsInProgress {
+                sequence_number, ..
+            }
+            | Self::McpListToolsCompleted {
+                sequence_number, ..
+            }
+            | Self::McpListToolsFailed {
+                sequence_number, ..
+            }
+            | Self::CustomToolCallInputDelta {
+                sequence_number, ..
+            }
+            | Self::CustomToolCallInputDone {
+                sequence_number, ..
+            }
+            | Self::Error {
+                sequence_number, ..
+            } => Some(*sequence_number),
+            Self::Unknown(value) => value.get("sequence_number").and_then(Value::as_u64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::models::responses::{
+        OutputContent, ResponseOutputItem, ResponseStatus, streaming::ResponseEvent,
+    };
+
+    #[test]
+    fn test_deserialize() {
+        let event: ResponseEvent = serde_json::from_value(json!({
+            "type": "response.created",
+            "sequence_number": 0,
+            "response": {
+                "id": "resp_123",
+                "object": "response",
+                "created_at": 1741476542,
+                "status": "in_progress",
+                "model": "gpt-4o",
+                "output": [],
+                "error": null,
+                "incomplete_details": null,
+                "usage": null,
+            },
+        }))
+        .unwrap();
+        let ResponseEvent::Created { response, .. } = &event else {
+            panic!("unexpected event: {event:?}");
+        };
+        assert_eq!(response.status, Some(ResponseStatus::InProgress));
+
+        let event: ResponseEvent = serde_json::from_value(json!({
+            "type": "response.output_item.added",
+            "sequence_number": 1,
+            "output_index": 0,
+            "item": {"id": "msg_123", "type": "message", "status": "in_progress", "role": "assistant", "content": []},
+        }))
+        .unwrap();
+        assert!(matches!(
+            event,
+            ResponseEvent::OutputItemAdded {
+                item: ResponseOutputItem::Message(_),
+                ..
+            }
+        ));
+
+        let event: ResponseEvent = serde_json::from_value(json!({
+            "type": "response.content_part.added",
+            "sequence_number": 2,
+            "item_id": "msg_123",
+            "output_index": 0,
+            "content_index": 0,
+            "part": {"type": "output_text", "text": "", "annotations": []},
+        }))
+        .unwrap();
+        assert!(matches!(
+            event,
+            ResponseEvent::ContentPartAdded {
+                part: OutputContent::OutputText(_),
+                ..
+            }
+        ));
+
+        let event: ResponseEvent = serde_json::from_value(json!({
+            "type": "response.output_text.delta",
+            "sequence_number": 3,
+            "item_id": "msg_123",
+            "output_index": 0,
+            "content_index": 0,
+            "delta": "Hi",
+        }))
+        .unwrap();
+        assert!(matches!(event, ResponseEvent::OutputTextDelta { ref delta, .. } if delta == "Hi"));
+        assert_eq!(event.sequence_number(), Some(3));
+    }
+
+    #[test]
+    fn test_deserialize_unknown() {
+        let event: ResponseEvent = serde_json::from_value(json!({
+            "type": "response.teleportation.started",
+            "sequence_number": 7,
+        }))
+        .unwrap();
+        assert!(matches!(event, ResponseEvent::Unknown(_)));
+        assert_eq!(event.sequence_number(), Some(7));
+
+        let item: ResponseOutputItem = serde_json::from_value(json!({
+            "type": "web_search_call",
+            "id": "ws_123",
+            "status": "completed",
+        }))
+        .unwrap();
+        assert!(matches!(item, ResponseOutputItem::Unknown(_)));
+        assert_eq!(item.id(), Some("ws_123"));
+    }
+}
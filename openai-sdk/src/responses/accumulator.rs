@@ -0,0 +1,497 @@
+//! Reassembly of streamed responses.
+
+use futures::StreamExt;
+use snafu::Snafu;
+
+use crate::{
+    error::ApiError,
+    models::responses::{
+        FunctionToolCall, OutputContent, OutputText, ReasoningContent, ReasoningItem,
+        ReasoningSummaryPart, Response, ResponseOutputItem, streaming::ResponseEvent,
+    },
+    transport::streaming::{OpenAIStreamingError, ParsedEventStream},
+};
+
+/// Error of [`ResponseAccumulator::push`] and [`ParsedEventStream::collect_response`].
+#[derive(Debug, Snafu)]
+pub enum ResponseStreamError {
+    #[snafu(transparent)]
+    Stream { source: OpenAIStreamingError },
+    /// The stream ended with a `response.failed` event. The error is in [`Response::error`].
+    #[snafu(display("Response failed: {}", response.error.as_ref().map_or("unknown error", |error| &error.message)))]
+    Failed { response: Box<Response> },
+}
+
+/// Folds the events of a response stream into the [`Response`] they describe.
+///
+/// The snapshot returned by [`ResponseAccumulator::response`] is updated with every event, so partial output can be rendered while the response is generated.
+///
+/// ```ignore
+/// let mut accumulator = ResponseAccumulator::new();
+/// while let Some(event) = stream.next().await {
+///     if let Some(response) = accumulator.push(&event?)? {
+///         println!("{}", response.output_text());
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ResponseAccumulator {
+    response: Option<Response>,
+}
+
+impl ResponseAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The response as far as it was streamed. `None` until the first event carrying the response, usually `response.created`.
+    pub fn response(&self) -> Option<&Response> {
+        self.response.as_ref()
+    }
+
+    pub fn into_response(self) -> Option<Response> {
+        self.response
+    }
+
+    /// Applies `event` to the snapshot. Returns the final response once it is completed, either with `response.completed` or with `response.incomplete`.
+    ///
+    /// Fails with [`ResponseStreamError::Failed`] on `response.failed` and with [`OpenAIStreamingError::Api`] on `error` events. Events referring to output the snapshot doesn't contain are ignored.
+    pub fn push(
+        &mut self,
+        event: &ResponseEvent,
+    ) -> Result<Option<&Response>, ResponseStreamError> {
+        match event {
+            ResponseEvent::Created { response, .. }
+            | ResponseEvent::Queued { response, .. }
+            | ResponseEvent::InProgress { response, .. } => self.replace(response),
+            ResponseEvent::Completed { response, .. }
+            | ResponseEvent::Incomplete { response, .. } => {
+                self.replace(response);
+                return Ok(self.response.as_ref());
+            }
+            ResponseEvent::Failed { response, .. } => {
+                self.replace(response);
+                return Err(ResponseStreamError::Failed {
+                    response: Box::new(response.clone()),
+                });
+            }
+            ResponseEvent::Error {
+                code,
+                message,
+                param,
+                ..
+            } => {
+                return Err(OpenAIStreamingError::Api {
+                    error: Box::new(ApiError {
+                        message: message.clone(),
+                        r#type: None,
+                        param: param.clone(),
+                        code: code.clone().map(Into::into),
+                    }),
+                }
+                .into());
+            }
+            ResponseEvent::OutputItemAdded {
+                output_index, item, ..
+            }
+            | ResponseEvent::OutputItemDone {
+                output_index, item, ..
+            } => {
+                if let Some(response) = &mut self.response {
+                    set(&mut response.output, *output_index, item.clone());
+                }
+            }
+            ResponseEvent::ContentPartAdded {
+                output_index,
+                content_index,
+                part,
+                ..
+            }
+            | ResponseEvent::ContentPartDone {
+                output_index,
+                content_index,
+                part,
+                ..
+            } => {
+                if let Some(ResponseOutputItem::Message(message)) = self.item(*output_index) {
+                    set(&mut message.content, *content_index, part.clone());
+                }
+            }
+            ResponseEvent::OutputTextDelta {
+                output_index,
+                content_index,
+                delta,
+                logprobs,
+                ..
+            } => {
+                if let Some(output) = self.output_text(*output_index, *content_index) {
+                    output.text.push_str(delta);
+                    if !logprobs.is_empty() {
+                        output
+                            .logprobs
+                            .get_or_insert_default()
+                            .extend(logprobs.iter().cloned());
+                    }
+                }
+            }
+            ResponseEvent::OutputTextDone {
+                output_index,
+                content_index,
+                text,
+                ..
+            } => {
+                if let Some(output) = self.output_text(*output_index, *content_index) {
+                    output.text.clone_from(text);
+                }
+            }
+            ResponseEvent::OutputTextAnnotationAdded {
+                output_index,
+                content_index,
+                annotation_index,
+                annotation,
+                ..
+            } => {
+                if let Some(output) = self.output_text(*output_index, *content_index) {
+                    set(
+                        &mut output.annotations,
+                        *annotation_index,
+                        annotation.clone(),
+                    );
+                }
+            }
+            ResponseEvent::RefusalDelta {
+                output_index,
+                content_index,
+                delta,
+                ..
+            } => {
+                if let Some(refusal) = self.refusal(*output_index, *content_index) {
+                    refusal.push_str(delta);
+                }
+            }
+            ResponseEvent::RefusalDone {
+                output_index,
+                content_index,
+                refusal: text,
+                ..
+            } => {
+                if let Some(refusal) = self.refusal(*output_index, *content_index) {
+                    refusal.clone_from(text);
+                }
+            }
+            ResponseEvent::FunctionCallArgumentsDelta {
+                output_index,
+                delta,
+                ..
+            } => {
+                if let Some(call) = self.function_call(*output_index) {
+                    call.arguments.push_str(delta);
+                }
+            }
+            ResponseEvent::FunctionCallArgumentsDone {
+                output_index,
+                arguments,
+                ..
+            } => {
+                if let Some(call) = self.function_call(*output_index) {
+                    call.arguments.clone_from(arguments);
+                }
+            }
+            ResponseEvent::ReasoningSummaryPartAdded {
+                output_index,
+                summary_index,
+                part,
+                ..
+            }
+            | ResponseEvent::ReasoningSummaryPartDone {
+                output_index,
+                summary_index,
+                part,
+                ..
+            } => {
+                if let Some(reasoning) = self.reasoning(*output_index) {
+                    set(&mut reasoning.summary, *summary_index, part.clone());
+                }
+            }
+            ResponseEvent::ReasoningSummaryTextDelta {
+                output_index,
+                summary_index,
+                delta,
+                ..
+            } => {
+                if let Some(ReasoningSummaryPart::SummaryText { text }) = self
+                    .reasoning(*output_index)
+                    .and_then(|reasoning| reasoning.summary.get_mut(*summary_index))
+                {
+                    text.push_str(delta);
+                }
+            }
+            ResponseEvent::ReasoningSummaryTextDone {
+                output_index,
+                summary_index,
+                text: done,
+                ..
+            } => {
+                if let Some(ReasoningSummaryPart::SummaryText { text }) = self
+                    .reasoning(*output_index)
+                    .and_then(|reasoning| reasoning.summary.get_mut(*summary_index))
+                {
+                    text.clone_from(done);
+                }
+            }
+            ResponseEvent::ReasoningTextDelta {
+                output_index,
+                content_index,
+                delta,
+                ..
+            } => {
+                if let Some(text) = self.reasoning_text(*output_index, *content_index) {
+                    text.push_str(delta);
+                }
+            }
+            ResponseEvent::ReasoningTextDone {
+                output_index,
+                content_index,
+                text: done,
+                ..
+            } => {
+                if let Some(text) = self.reasoning_text(*output_index, *content_index) {
+                    text.clone_from(done);
+                }
+            }
+            // Built-in tool calls only report progress; their results arrive with `response.output_item.done`.
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    /// Replaces the snapshot, keeping the output streamed so far if the new response doesn't carry any.
+    fn replace(&mut self, response: &Response) {
+        let output = self
+            .response
+            .take()
+            .map(|previous| previous.output)
+            .unwrap_or_default();
+
+        let mut response = response.clone();
+        if response.output.is_empty() {
+            response.output = output;
+        }
+        self.response = Some(response);
+    }
+
+    fn item(&mut self, output_index: usize) -> Option<&mut ResponseOutputItem> {
+        self.response.as_mut()?.output.get_mut(output_index)
+    }
+
+    fn content(&mut self, output_index: usize, content_index: usize) -> Option<&mut OutputContent> {
+        match self.item(output_index)? {
+            ResponseOutputItem::Message(message) => message.content.get_mut(content_index),
+            _ => None,
+        }
+    }
+
+    fn output_text(
+        &mut self,
+        output_index: usize,
+        content_index: usize,
+    ) -> Option<&mut OutputText> {
+        match self.content(output_index, content_index)? {
+            OutputContent::OutputText(output) => Some(output),
+            _ => None,
+        }
+    }
+
+    fn refusal(&mut self, output_index: usize, content_index: usize) -> Option<&mut String> {
+        match self.content(output_index, content_index)? {
+            OutputContent::Refusal { refusal } => Some(refusal),
+            _ => None,
+        }
+    }
+
+    fn function_call(&mut self, output_index: usize) -> Option<&mut FunctionToolCall> {
+        match self.item(output_index)? {
+            ResponseOutputItem::FunctionCall(call) => Some(call),
+            _ => None,
+        }
+    }
+
+    fn reasoning(&mut self, output_index: usize) -> Option<&mut ReasoningItem> {
+        match self.item(output_index)? {
+            ResponseOutputItem::Reasoning(reasoning) => Some(reasoning),
+            _ => None,
+        }
+    }
+
+    /// Reasoning text parts aren't announced by an event of their own, so missing parts are created by their first delta.
+    fn reasoning_text(&mut self, output_index: usize, content_index: usize) -> Option<&mut String> {
+        let content = self
+            .reasoning(output_index)?
+            .content
+            .get_or_insert_default();
+
+        if content_index == content.len() {
+            content.push(ReasoningContent::ReasoningText {
+                text: String::new(),
+            });
+        }
+
+        match content.get_mut(content_index)? {
+            ReasoningContent::ReasoningText { text } => Some(text),
+        }
+    }
+}
+
+/// Replaces the element at `index`, or appends it if `index` is the length of the vector.
+fn set<T>(items: &mut Vec<T>, index: usize, item: T) {
+    if let Some(slot) = items.get_mut(index) {
+        *slot = item;
+    } else if index == items.len() {
+        items.push(item);
+    }
+}
+
+impl ParsedEventStream<ResponseEvent> {
+    /// Reads the stream until the response is completed and returns it. See [`ResponseAccumulator::push`] for the errors.
+    pub async fn collect_response(mut self) -> Result<Response, ResponseStreamError> {
+        let mut accumulator = ResponseAccumulator::new();
+
+        while let Some(event) = self.next().await {
+            if let Some(response) = accumulator.push(&event?)? {
+                return Ok(response.clone());
+            }
+        }
+
+        Err(OpenAIStreamingError::UnexpectedEnd.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use eventsource_stream::Event;
+    use futures::{StreamExt, stream};
+    use serde_json::{Value, json};
+
+    use crate::{
+        models::responses::{ResponseOutputItem, ResponseStatus, streaming::ResponseEvent},
+        responses::accumulator::{ResponseAccumulator, ResponseStreamError},
+        transport::{
+            meta::ResponseMeta,
+            streaming::{OpenAIStreamingError, ParsedEventStream},
+        },
+    };
+
+    fn response(status: &str, output: Value) -> Value {
+        json!({
+            "id": "resp_123",
+            "object": "response",
+            "created_at": 1741476542,
+            "status": status,
+            "model": "gpt-4o",
+            "output": output,
+            "error": null,
+            "incomplete_details": null,
+            "usage": null,
+        })
+    }
+
+    fn events() -> Vec<Value> {
+        vec![
+            json!({"type": "response.created", "sequence_number": 0, "response": response("in_progress", json!([]))}),
+            json!({"type": "response.in_progress", "sequence_number": 1, "response": response("in_progress", json!([]))}),
+            json!({"type": "response.output_item.added", "sequence_number": 2, "output_index": 0, "item": {"id": "msg_123", "type": "message", "status": "in_progress", "role": "assistant", "content": []}}),
+            json!({"type": "response.content_part.added", "sequence_number": 3, "item_id": "msg_123", "output_index": 0, "content_index": 0, "part": {"type": "output_text", "text": "", "annotations": []}}),
+            json!({"type": "response.output_text.delta", "sequence_number": 4, "item_id": "msg_123", "output_index": 0, "content_index": 0, "delta": "Hello"}),
+            json!({"type": "response.output_text.delta", "sequence_number": 5, "item_id": "msg_123", "output_index": 0, "content_index": 0, "delta": " there"}),
+            json!({"type": "response.output_item.added", "sequence_number": 6, "output_index": 1, "item": {"id": "fc_123", "type": "function_call", "status": "in_progress", "call_id": "call_123", "name": "get_weather", "arguments": ""}}),
+            json!({"type": "response.function_call_arguments.delta", "sequence_number": 7, "item_id": "fc_123", "output_index": 1, "delta": "{\"city\":"}),
+            json!({"type": "response.function_call_arguments.delta", "sequence_number": 8, "item_id": "fc_123", "output_index": 1, "delta": "\"Paris\"}"}),
+            json!({
+                "type": "response.completed",
+                "sequence_number": 9,
+                "response": response("completed", json!([
+                    {"id": "msg_123", "type": "message", "status": "completed", "role": "assistant", "content": [{"type": "output_text", "text": "Hello there", "annotations": []}]},
+                    {"id": "fc_123", "type": "function_call", "status": "completed", "call_id": "call_123", "name": "get_weather", "arguments": "{\"city\":\"Paris\"}"},
+                ])),
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_push() {
+        let mut accumulator = ResponseAccumulator::new();
+        let events = events();
+
+        for event in &events[..9] {
+            let event: ResponseEvent = serde_json::from_value(event.clone()).unwrap();
+            assert!(accumulator.push(&event).unwrap().is_none());
+        }
+
+        let snapshot = accumulator.response().unwrap();
+        assert_eq!(snapshot.status, Some(ResponseStatus::InProgress));
+        assert_eq!(snapshot.output_text(), "Hello there");
+        let ResponseOutputItem::FunctionCall(call) = &snapshot.output[1] else {
+            panic!("unexpected item: {:?}", snapshot.output[1]);
+        };
+        assert_eq!(call.arguments, r#"{"city":"Paris"}"#);
+
+        let event: ResponseEvent = serde_json::from_value(events[9].clone()).unwrap();
+        let response = accumulator.push(&event).unwrap().unwrap();
+        assert_eq!(response.status, Some(ResponseStatus::Completed));
+        assert_eq!(response.output.len(), 2);
+    }
+
+    #[test]
+    fn test_push_failed() {
+        let mut accumulator = ResponseAccumulator::new();
+
+        let mut failed = response("failed", json!([]));
+        failed["error"] = json!({"code": "server_error", "message": "The server had an error"});
+        let event: ResponseEvent = serde_json::from_value(
+            json!({"type": "response.failed", "sequence_number": 1, "response": failed}),
+        )
+        .unwrap();
+        assert!(matches!(
+            accumulator.push(&event),
+            Err(ResponseStreamError::Failed { response }) if response.error.as_ref().unwrap().code == "server_error"
+        ));
+
+        let event: ResponseEvent = serde_json::from_value(json!({"type": "error", "sequence_number": 2, "code": "rate_limit_exceeded", "message": "Slow down", "param": null})).unwrap();
+        let Err(ResponseStreamError::Stream {
+            source: OpenAIStreamingError::Api { error },
+        }) = accumulator.push(&event)
+        else {
+            panic!("expected an API error");
+        };
+        assert!(error.is_rate_limited());
+    }
+
+    #[tokio::test]
+    async fn test_collect_response() {
+        let stream = |events: Vec<Value>| {
+            let events = events.into_iter().map(|event| {
+                Ok(Event {
+                    data: event.to_string(),
+                    ..Default::default()
+                })
+            });
+            ParsedEventStream::<ResponseEvent>::from_event_stream(
+                stream::iter(events).boxed(),
+                ResponseMeta::default(),
+            )
+        };
+
+        let response = stream(events()).collect_response().await.unwrap();
+        assert_eq!(response.output_text(), "Hello there");
+
+        let mut events = events();
+        events.pop();
+        assert!(matches!(
+            stream(events).collect_response().await,
+            Err(ResponseStreamError::Stream {
+                source: OpenAIStreamingError::UnexpectedEnd
+            })
+        ));
+    }
+}
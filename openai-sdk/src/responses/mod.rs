@@ -1,43 +1,88 @@
 use std::marker::PhantomData;
 
-use reqwest::Method;
-use serde::Serialize;
+use http::Method;
+use serde::{Serialize, de::DeserializeOwned};
 use serde_bool::False;
 use serde_with::skip_serializing_none;
 
+#[cfg(feature = "tracing")]
+use crate::telemetry::{self, Traced};
 use crate::{
     OpenAI, OpenAIError,
-    models::responses::{Response, ResponseIdRef, ResponseInput},
-    transport::Transport,
+    models::{
+        DeletionStatus,
+        responses::{Response, ResponseId, ResponseIdRef, ResponseInput},
+    },
+    transport::{RequestBody, RequestOptions, Transport, meta::WithMeta},
 };
 
+#[cfg(feature = "responses-streaming")]
+pub mod accumulator;
 #[cfg(feature = "responses-streaming")]
 mod streaming;
 
+#[cfg(feature = "responses-streaming")]
+pub use streaming::ResumableStream;
+
 pub struct ResponsesHandler<'a, T, Stream> {
     pub(crate) client: &'a OpenAI<T>,
+    pub(crate) options: RequestOptions,
     pub(crate) _marker: PhantomData<Stream>,
 }
 
+impl<T, Stream> ResponsesHandler<'_, T, Stream> {
+    /// Overrides the transport configuration for requests made through this handler.
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
 impl<T: Transport> ResponsesHandler<'_, T, False> {
     /// Creates a model response. Provide [text](https://platform.openai.com/docs/guides/text) or [image](https://platform.openai.com/docs/guides/images) inputs to generate [text](https://platform.openai.com/docs/guides/text) or [JSON](https://platform.openai.com/docs/guides/structured-outputs) outputs. Have the model call your own [custom code](https://platform.openai.com/docs/guides/function-calling) or use built-in [tools](https://platform.openai.com/docs/guides/tools) like [web search](https://platform.openai.com/docs/guides/tools-web-search) or [file search](https://platform.openai.com/docs/guides/tools-file-search) to use your own data as input for the model's response.
     ///
     /// https://platform.openai.com/docs/api-reference/responses/create
     pub async fn create(&self, params: &ResponseParams<False>) -> Result<Response, OpenAIError> {
-        self.client
-            .transport
-            .send(Method::POST, "/v1/responses", Some(params))
+        self.create_with_meta(params)
             .await
+            .map(WithMeta::into_inner)
+    }
+
+    /// Like [`Self::create`], but also returns the response metadata.
+    pub async fn create_with_meta(
+        &self,
+        params: &ResponseParams<False>,
+    ) -> Result<WithMeta<Response>, OpenAIError> {
+        self.send(
+            "chat",
+            params.model.as_deref(),
+            Method::POST,
+            "/v1/responses",
+            RequestBody::Json(params),
+        )
+        .await
     }
 
     /// Retrieves a model response with the given ID.
     ///
     /// https://platform.openai.com/docs/api-reference/responses/get
     pub async fn get(&self, id: &ResponseIdRef) -> Result<Response, OpenAIError> {
-        self.client
-            .transport
-            .send(Method::GET, &format!("/v1/responses/{id}"), None::<&()>)
-            .await
+        self.get_with_meta(id).await.map(WithMeta::into_inner)
+    }
+
+    /// Like [`Self::get`], but also returns the response metadata.
+    pub async fn get_with_meta(
+        &self,
+        id: &ResponseIdRef,
+    ) -> Result<WithMeta<Response>, OpenAIError> {
+        self.send(
+            "get_response",
+            None,
+            Method::GET,
+            &format!("/v1/responses/{id}"),
+            RequestBody::none(),
+        )
+        .await
     }
 }
 
@@ -45,24 +90,92 @@ impl<T: Transport, Stream> ResponsesHandler<'_, T, Stream> {
     /// Deletes a model response with the given ID.
     ///
     /// https://platform.openai.com/docs/api-reference/responses/delete
-    pub async fn delete(&self, id: &ResponseIdRef) -> Result<(), OpenAIError> {
-        self.client
-            .transport
-            .send(Method::DELETE, &format!("/v1/responses/{id}"), None::<&()>)
-            .await
+    pub async fn delete(
+        &self,
+        id: &ResponseIdRef,
+    ) -> Result<DeletionStatus<ResponseId>, OpenAIError> {
+        self.delete_with_meta(id).await.map(WithMeta::into_inner)
+    }
+
+    /// Like [`Self::delete`], but also returns the response metadata.
+    pub async fn delete_with_meta(
+        &self,
+        id: &ResponseIdRef,
+    ) -> Result<WithMeta<DeletionStatus<ResponseId>>, OpenAIError> {
+        self.send(
+            "delete_response",
+            None,
+            Method::DELETE,
+            &format!("/v1/responses/{id}"),
+            RequestBody::none(),
+        )
+        .await
     }
 
     /// Cancels a model response with the given ID. Only responses created with the `background` parameter set to `true` can be cancelled. [Learn more.](https://platform.openai.com/docs/guides/background)
     ///
     /// https://platform.openai.com/docs/api-reference/responses/cancel
     pub async fn cancel(&self, id: &ResponseIdRef) -> Result<Response, OpenAIError> {
+        self.cancel_with_meta(id).await.map(WithMeta::into_inner)
+    }
+
+    /// Like [`Self::cancel`], but also returns the response metadata.
+    pub async fn cancel_with_meta(
+        &self,
+        id: &ResponseIdRef,
+    ) -> Result<WithMeta<Response>, OpenAIError> {
+        self.send(
+            "cancel_response",
+            None,
+            Method::POST,
+            &format!("/v1/responses/{id}/cancel"),
+            RequestBody::none(),
+        )
+        .await
+    }
+}
+
+impl<T: Transport, Stream> ResponsesHandler<'_, T, Stream> {
+    /// Sends a request through the transport, inside a span following the gen_ai semantic conventions.
+    #[cfg(feature = "tracing")]
+    async fn send<P, R>(
+        &self,
+        operation: &'static str,
+        model: Option<&str>,
+        method: Method,
+        path: &str,
+        body: RequestBody<'_, P>,
+    ) -> Result<WithMeta<R>, OpenAIError>
+    where
+        P: Sync + Serialize,
+        R: DeserializeOwned + Traced,
+    {
+        let span = telemetry::operation_span(operation, model);
+        let response = self
+            .client
+            .transport
+            .send(method, path, body, &self.options);
+
+        telemetry::instrument_operation(span, response).await
+    }
+
+    /// Sends a request through the transport.
+    #[cfg(not(feature = "tracing"))]
+    async fn send<P, R>(
+        &self,
+        _operation: &'static str,
+        _model: Option<&str>,
+        method: Method,
+        path: &str,
+        body: RequestBody<'_, P>,
+    ) -> Result<WithMeta<R>, OpenAIError>
+    where
+        P: Sync + Serialize,
+        R: DeserializeOwned,
+    {
         self.client
             .transport
-            .send(
-                Method::POST,
-                &format!("/v1/responses/{id}/cancel"),
-                None::<&()>,
-            )
+            .send(method, path, body, &self.options)
             .await
     }
 }
@@ -77,20 +190,33 @@ pub struct ResponseParams<Stream = False> {
     // TODO: conversation
     /// Text, image, or file inputs to the model, used to generate a response.
     pub input: Option<ResponseInput>,
+    /// Model ID used to generate the response, like `gpt-4o` or `o3`. OpenAI offers a wide range of models with different capabilities, performance characteristics, and price points. Refer to the [model guide](https://platform.openai.com/docs/models) to browse and compare available models.
+    pub model: Option<String>,
     /// If set to true, the model response data will be streamed to the client as it is generated using [server-sent events.](https://developer.mozilla.org/en-US/docs/Web/API/Server-sent_events/Using_server-sent_events#Event_stream_format) See the [Streaming section below](https://platform.openai.com/docs/api-reference/responses-streaming) for more information.
     pub stream: Stream,
 }
 
 #[cfg(test)]
 mod test {
+    #[cfg(feature = "reqwest")]
     use dotenv_codegen::dotenv;
+    use http::{Method, StatusCode, header::HeaderName};
     use serde_bool::False;
+    use serde_json::json;
 
-    use crate::{OpenAI, OpenAIError};
+    use crate::{
+        OpenAI, OpenAIError,
+        models::responses::ResponseInput,
+        responses::ResponseParams,
+        transport::{RequestOptions, mock::MockTransport},
+    };
 
+    #[cfg(feature = "reqwest")]
     const OPENAI_API_KEY: &str = dotenv!("OPENAI_API_KEY");
+    #[cfg(feature = "reqwest")]
     const RESPONSE_ID: &str = dotenv!("RESPONSE_ID");
 
+    #[cfg(feature = "reqwest")]
     #[tokio::test]
     async fn test_get() -> Result<(), OpenAIError> {
         let client = OpenAI::standard_http(OPENAI_API_KEY.into(), Default::default());
@@ -101,4 +227,74 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_mock_create() -> Result<(), OpenAIError> {
+        let mock = MockTransport::new();
+        mock.expect(Method::POST, "/v1/responses")
+            .with_json_body(json!({"input": "Hello", "stream": false, "store": false}))
+            .reply_header(HeaderName::from_static("x-request-id"), "req_123")
+            .respond_json(
+                StatusCode::OK,
+                json!({"id": "resp_123", "created_at": 1741476542, "background": false, "model": "gpt-4o"}),
+            );
+
+        let client = OpenAI::new(mock.clone());
+
+        let params = ResponseParams {
+            input: Some(ResponseInput::Text("Hello".to_string())),
+            ..Default::default()
+        };
+        let options = RequestOptions {
+            extra_body: json!({"store": false}).as_object().unwrap().clone(),
+            ..Default::default()
+        };
+
+        let response = client
+            .responses::<False>()
+            .with_options(options)
+            .create_with_meta(&params)
+            .await?;
+
+        assert_eq!(response.data.id.0, "resp_123");
+        assert_eq!(response.meta.request_id.as_deref(), Some("req_123"));
+
+        mock.assert_done();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mock_delete() -> Result<(), OpenAIError> {
+        let mock = MockTransport::new();
+        mock.expect(Method::DELETE, "/v1/responses/resp_123")
+            .respond_json(
+                StatusCode::OK,
+                json!({"id": "resp_123", "object": "response", "deleted": true}),
+            );
+        mock.expect(Method::DELETE, "/v1/responses/resp_456")
+            .respond_json(
+                StatusCode::NOT_FOUND,
+                json!({"error": {"message": "Response not found", "type": "invalid_request_error", "param": null, "code": null}}),
+            );
+
+        let client = OpenAI::new(mock.clone());
+
+        let deleted = client
+            .responses::<False>()
+            .delete("resp_123".into())
+            .await?;
+        assert!(deleted.deleted);
+
+        let err = client
+            .responses::<False>()
+            .delete("resp_456".into())
+            .await
+            .unwrap_err();
+        assert_eq!(err.api_error().unwrap().message, "Response not found");
+
+        mock.assert_done();
+
+        Ok(())
+    }
 }
@@ -1,46 +1,336 @@
-use reqwest::Method;
+use std::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{Stream, StreamExt, stream::BoxStream};
+use http::Method;
+use serde::Serialize;
 use serde_bool::True;
+use serde_with::skip_serializing_none;
 
+#[cfg(feature = "tracing")]
+use crate::telemetry;
 use crate::{
     OpenAIError,
-    models::responses::{ResponseIdRef, streaming::ResponseEvent},
+    models::responses::{ResponseId, ResponseIdRef, streaming::ResponseEvent},
     responses::{ResponseParams, ResponsesHandler},
-    transport::streaming::{ParsedEventStream, StreamingTransport},
+    transport::{
+        RequestBody,
+        retry::RetryPolicy,
+        streaming::{OpenAIStreamingError, ParsedEventStream, StreamingTransport},
+    },
 };
 
+/// Query of a request reopening the event stream of a response.
+#[skip_serializing_none]
+#[derive(Serialize)]
+struct StreamQuery {
+    stream: True,
+    starting_after: Option<u64>,
+}
+
 impl<T: StreamingTransport> ResponsesHandler<'_, T, True> {
     pub async fn create(
         &self,
         params: &ResponseParams<True>,
     ) -> Result<ParsedEventStream<ResponseEvent>, OpenAIError> {
-        self.client
-            .transport
-            .send(Method::POST, "/v1/responses", Some(params))
-            .await
+        self.send_streaming(
+            params.model.as_deref(),
+            Method::POST,
+            "/v1/responses",
+            RequestBody::Json(params),
+        )
+        .await
     }
 
+    /// Streams the events of a model response with the given ID from the beginning. Only responses created with `background` set to `true` can be streamed again.
     pub async fn get(
         &self,
         id: &ResponseIdRef,
     ) -> Result<ParsedEventStream<ResponseEvent>, OpenAIError> {
-        self.client
+        self.get_stream(id, None).await
+    }
+
+    /// Streams the events of a model response with the given ID that follow the event with the sequence number `starting_after`.
+    pub async fn get_starting_after(
+        &self,
+        id: &ResponseIdRef,
+        starting_after: u64,
+    ) -> Result<ParsedEventStream<ResponseEvent>, OpenAIError> {
+        self.get_stream(id, Some(starting_after)).await
+    }
+
+    async fn get_stream(
+        &self,
+        id: &ResponseIdRef,
+        starting_after: Option<u64>,
+    ) -> Result<ParsedEventStream<ResponseEvent>, OpenAIError> {
+        self.send_streaming(
+            None,
+            Method::GET,
+            &format!("/v1/responses/{id}"),
+            RequestBody::Query(&StreamQuery {
+                stream: True,
+                starting_after,
+            }),
+        )
+        .await
+    }
+
+    /// Starts a stream through the transport, inside a span following the gen_ai semantic conventions if tracing is enabled. The span stays open until the stream ends, so it records the usage of the completed response.
+    async fn send_streaming<P>(
+        &self,
+        model: Option<&str>,
+        method: Method,
+        path: &str,
+        body: RequestBody<'_, P>,
+    ) -> Result<ParsedEventStream<ResponseEvent>, OpenAIError>
+    where
+        P: Sync + Serialize,
+    {
+        #[cfg(feature = "tracing")]
+        let span = telemetry::operation_span("chat", model);
+        #[cfg(not(feature = "tracing"))]
+        let _ = model;
+
+        let stream = self
+            .client
             .transport
-            .send(Method::GET, &format!("/v1/responses/{id}"), None::<&()>)
-            .await
+            .send(method, path, body, &self.options);
+
+        #[cfg(feature = "tracing")]
+        let stream = tracing::Instrument::instrument(stream, span.clone());
+
+        let stream = stream.await;
+
+        #[cfg(feature = "tracing")]
+        let stream = match stream {
+            Ok(stream) => {
+                span.record("openai.request.id", stream.meta().request_id.as_deref());
+                Ok(stream.with_span(span))
+            }
+            Err(err) => {
+                telemetry::record_error(&span, &err);
+                Err(err)
+            }
+        };
+
+        stream
+    }
+}
+
+impl<'a, T> ResponsesHandler<'a, T, True>
+where
+    T: StreamingTransport + Sync,
+{
+    /// Like [`Self::create`], but reopens the stream where it was interrupted, see [`ResumableStream`]. The response must be created with `background` set to `true` for the stream to be resumable.
+    pub async fn create_resumable(
+        &self,
+        params: &ResponseParams<True>,
+        retry: RetryPolicy,
+    ) -> Result<ResumableStream<'a>, OpenAIError> {
+        let stream = self.create(params).await?;
+        Ok(ResumableStream::new(
+            self.reborrow(),
+            None,
+            Some(stream),
+            retry,
+        ))
+    }
+
+    /// Like [`Self::get`], but reopens the stream where it was interrupted, see [`ResumableStream`]. The stream is opened when it is first polled.
+    pub fn get_resumable(&self, id: &ResponseIdRef, retry: RetryPolicy) -> ResumableStream<'a> {
+        let id = ResponseId(id.to_string());
+        ResumableStream::new(self.reborrow(), Some(id), None, retry)
+    }
+
+    fn reborrow(&self) -> ResponsesHandler<'a, T, True> {
+        ResponsesHandler {
+            client: self.client,
+            options: self.options.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// The events of a background response, reopening the stream with `starting_after` when the connection is lost.
+///
+/// The stream is reopened after errors accepted by [`OpenAIStreamingError::is_retryable`] and when the connection closes before the response is completed, failed or incomplete. Events replayed by the server are skipped based on their sequence number. After `retry.max_attempts` attempts in a row that deliver no new event, the last error is returned and the stream ends; reopening requests that fail with errors rejected by `retry.retry_on` end it right away.
+pub struct ResumableStream<'a> {
+    inner: BoxStream<'a, Result<ResponseEvent, OpenAIStreamingError>>,
+}
+
+struct ResumeState<'a, T> {
+    handler: ResponsesHandler<'a, T, True>,
+    /// Known from the start when resuming an existing response, or from the `response.created` event otherwise.
+    id: Option<ResponseId>,
+    stream: Option<ParsedEventStream<ResponseEvent>>,
+    last_sequence_number: Option<u64>,
+    retry: RetryPolicy,
+    /// Attempts since the last new event.
+    attempts: u32,
+    done: bool,
+}
+
+impl<'a> ResumableStream<'a> {
+    fn new<T>(
+        handler: ResponsesHandler<'a, T, True>,
+        id: Option<ResponseId>,
+        stream: Option<ParsedEventStream<ResponseEvent>>,
+        retry: RetryPolicy,
+    ) -> Self
+    where
+        T: StreamingTransport + Sync,
+    {
+        let state = ResumeState {
+            handler,
+            id,
+            stream,
+            last_sequence_number: None,
+            retry,
+            attempts: 0,
+            done: false,
+        };
+
+        Self {
+            inner: futures::stream::unfold(state, |mut state| async move {
+                let item = state.next().await?;
+                Some((item, state))
+            })
+            .boxed(),
+        }
+    }
+}
+
+impl<T> ResumeState<'_, T>
+where
+    T: StreamingTransport + Sync,
+{
+    async fn next(&mut self) -> Option<Result<ResponseEvent, OpenAIStreamingError>> {
+        loop {
+            if self.done {
+                return None;
+            }
+
+            let Some(stream) = &mut self.stream else {
+                if let Err(err) = self.reopen().await {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+                continue;
+            };
+
+            let err = match stream.next().await {
+                Some(Ok(event)) => {
+                    if let Some(sequence_number) = event.sequence_number() {
+                        if self
+                            .last_sequence_number
+                            .is_some_and(|last| sequence_number <= last)
+                        {
+                            continue;
+                        }
+                        self.last_sequence_number = Some(sequence_number);
+                    }
+
+                    match &event {
+                        ResponseEvent::Created { response, .. } if self.id.is_none() => {
+                            self.id = Some(response.id.clone());
+                        }
+                        ResponseEvent::Completed { .. }
+                        | ResponseEvent::Failed { .. }
+                        | ResponseEvent::Incomplete { .. }
+                        | ResponseEvent::Error { .. } => self.done = true,
+                        _ => {}
+                    }
+
+                    self.attempts = 0;
+                    return Some(Ok(event));
+                }
+                Some(Err(err)) if err.is_retryable() => err,
+                Some(Err(err)) => {
+                    // The server closes the stream after an error event.
+                    self.done = matches!(err, OpenAIStreamingError::Api { .. });
+                    return Some(Err(err));
+                }
+                None => OpenAIStreamingError::UnexpectedEnd,
+            };
+
+            self.stream = None;
+            self.attempts += 1;
+
+            if self.id.is_none() || self.attempts >= self.retry.max_attempts {
+                self.done = true;
+                return Some(Err(err));
+            }
+
+            tokio::time::sleep(self.retry.delay(self.attempts, None)).await;
+        }
+    }
+
+    /// Opens the stream after the last received event, retrying errors accepted by the retry policy.
+    async fn reopen(&mut self) -> Result<(), OpenAIStreamingError> {
+        let id = self.id.clone().ok_or(OpenAIStreamingError::UnexpectedEnd)?;
+
+        loop {
+            let stream = match self.last_sequence_number {
+                Some(last) => self.handler.get_starting_after(id.as_ref(), last).await,
+                None => self.handler.get(id.as_ref()).await,
+            };
+
+            match stream {
+                Ok(stream) => {
+                    self.stream = Some(stream);
+                    return Ok(());
+                }
+                Err(err) => {
+                    self.attempts += 1;
+                    if !self.retry.retries(&err) || self.attempts >= self.retry.max_attempts {
+                        return Err(OpenAIStreamingError::Resume {
+                            source: Box::new(err),
+                        });
+                    }
+
+                    let headers = err.meta().map(|meta| &meta.headers);
+                    tokio::time::sleep(self.retry.delay(self.attempts, headers)).await;
+                }
+            }
+        }
+    }
+}
+
+impl Stream for ResumableStream<'_> {
+    type Item = Result<ResponseEvent, OpenAIStreamingError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
     }
 }
 
 #[cfg(test)]
 mod test {
+    #[cfg(feature = "reqwest")]
     use dotenv_codegen::dotenv;
     use futures::StreamExt;
+    use http::{Method, StatusCode};
     use serde_bool::True;
+    use serde_json::json;
 
-    use crate::{OpenAI, OpenAIError, models::responses::ResponseInput, responses::ResponseParams};
+    use crate::{
+        OpenAI,
+        models::responses::streaming::ResponseEvent,
+        transport::{mock::MockTransport, retry::RetryPolicy, streaming::OpenAIStreamingError},
+    };
+    #[cfg(feature = "reqwest")]
+    use crate::{OpenAIError, models::responses::ResponseInput, responses::ResponseParams};
 
+    #[cfg(feature = "reqwest")]
     const OPENAI_API_KEY: &str = dotenv!("OPENAI_API_KEY");
+    #[cfg(feature = "reqwest")]
     const RESPONSE_ID: &str = dotenv!("RESPONSE_ID");
 
+    #[cfg(feature = "reqwest")]
     #[tokio::test]
     async fn test_get() -> Result<(), OpenAIError> {
         let client = OpenAI::standard_http(OPENAI_API_KEY.into(), Default::default());
@@ -54,6 +344,7 @@ mod test {
         Ok(())
     }
 
+    #[cfg(feature = "reqwest")]
     #[tokio::test]
     async fn test_create() -> Result<(), OpenAIError> {
         let client = OpenAI::standard_http(OPENAI_API_KEY.into(), Default::default());
@@ -61,6 +352,7 @@ mod test {
         let params = ResponseParams {
             background: Some(true),
             input: Some(ResponseInput::Text("Hello".to_string())),
+            model: None,
             stream: True,
         };
 
@@ -72,4 +364,100 @@ mod test {
 
         Ok(())
     }
+
+    /// SSE frames of the events with the given sequence numbers, the last one completing the response.
+    fn sse(sequence_numbers: impl IntoIterator<Item = u64>, completed: u64) -> String {
+        sequence_numbers
+            .into_iter()
+            .map(|sequence_number| {
+                let event = if sequence_number == completed {
+                    let response = json!({"id": "resp_123", "created_at": 1741476542, "model": "gpt-4o", "status": "completed"});
+                    json!({"type": "response.completed", "sequence_number": sequence_number, "response": response})
+                } else {
+                    json!({"type": "response.output_text.delta", "sequence_number": sequence_number, "item_id": "msg_123", "output_index": 0, "content_index": 0, "delta": "a"})
+                };
+                format!("data: {event}\n\n")
+            })
+            .collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_get_resumable() {
+        let mock = MockTransport::new();
+        mock.expect(Method::GET, "/v1/responses/resp_123")
+            .with_query("stream", "true")
+            .respond_sse(StatusCode::OK, sse(0..3, 5));
+        mock.expect(Method::GET, "/v1/responses/resp_123")
+            .with_query("starting_after", "2")
+            .respond_sse(StatusCode::OK, sse(1..6, 5));
+
+        let client = OpenAI::new(mock.clone());
+        let events: Vec<ResponseEvent> = client
+            .responses::<True>()
+            .get_resumable("resp_123".into(), RetryPolicy::default())
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        let sequence_numbers: Vec<_> = events
+            .iter()
+            .filter_map(ResponseEvent::sequence_number)
+            .collect();
+        assert_eq!(sequence_numbers, [0, 1, 2, 3, 4, 5]);
+        assert!(matches!(events[5], ResponseEvent::Completed { .. }));
+
+        mock.assert_done();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_get_resumable_gives_up() {
+        let mock = MockTransport::new();
+        mock.expect(Method::GET, "/v1/responses/resp_123")
+            .respond_sse(StatusCode::OK, sse(0..2, 5));
+        mock.expect(Method::GET, "/v1/responses/resp_123")
+            .with_query("starting_after", "1")
+            .times(2)
+            .respond_sse(StatusCode::OK, sse(0..2, 5));
+
+        let client = OpenAI::new(mock.clone());
+        let results: Vec<Result<ResponseEvent, OpenAIStreamingError>> = client
+            .responses::<True>()
+            .get_resumable(
+                "resp_123".into(),
+                RetryPolicy {
+                    max_attempts: 3,
+                    ..Default::default()
+                },
+            )
+            .collect()
+            .await;
+
+        assert_eq!(results.len(), 3);
+        assert!(matches!(
+            results[2],
+            Err(OpenAIStreamingError::UnexpectedEnd)
+        ));
+
+        mock.assert_done();
+
+        let mock = MockTransport::new();
+        mock.expect(Method::GET, "/v1/responses/resp_123")
+            .respond_json(
+                StatusCode::NOT_FOUND,
+                json!({"error": {"message": "Response not found"}}),
+            );
+
+        let client = OpenAI::new(mock.clone());
+        let results: Vec<_> = client
+            .responses::<True>()
+            .get_resumable("resp_123".into(), RetryPolicy::default())
+            .collect()
+            .await;
+        assert!(matches!(
+            results[..],
+            [Err(OpenAIStreamingError::Resume { .. })]
+        ));
+
+        mock.assert_done();
+    }
 }
@@ -0,0 +1,696 @@
+//! Spans following the OpenTelemetry semantic conventions for [generative AI](https://opentelemetry.io/docs/specs/semconv/gen-ai/gen-ai-spans/) and [HTTP clients](https://opentelemetry.io/docs/specs/semconv/http/http-spans/#http-client-span).
+//!
+//! Request and response bodies are only recorded if enabled with [`StandardHttpTransportBuilder::trace_bodies`](crate::transport::StandardHttpTransportBuilder::trace_bodies).
+
+#[cfg(feature = "responses-streaming")]
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[cfg(feature = "responses-streaming")]
+use eventsource_stream::{Event, EventStreamError};
+#[cfg(feature = "responses-streaming")]
+use futures::{Stream, ready};
+use http::{Method, StatusCode, header::HeaderMap};
+use serde::{Deserialize, Serialize};
+use tracing::{Span, field::Empty};
+use url::Url;
+
+#[cfg(feature = "responses-streaming")]
+use crate::transport::{BoxError, streaming::EventStream};
+use crate::{
+    OpenAIError,
+    transport::{RequestBody, RequestOptions},
+};
+
+const PROVIDER: &str = "openai";
+const REQUEST_ID: &str = "x-request-id";
+
+/// Response types that carry gen_ai attributes.
+#[cfg(feature = "responses")]
+pub(crate) trait Traced {
+    fn record(&self, span: &Span) {
+        let _ = span;
+    }
+}
+
+#[cfg(feature = "responses")]
+impl<Id> Traced for crate::models::DeletionStatus<Id> {}
+
+#[cfg(feature = "responses")]
+impl Traced for crate::models::responses::Response {
+    fn record(&self, span: &Span) {
+        span.record("gen_ai.response.id", self.id.0.as_str());
+        span.record("gen_ai.response.model", self.model.as_str());
+
+        if let Some(usage) = &self.usage {
+            span.record("gen_ai.usage.input_tokens", usage.input_tokens);
+            span.record("gen_ai.usage.output_tokens", usage.output_tokens);
+        }
+    }
+}
+
+/// Creates the span of an API operation such as `chat`, named `{operation} {model}`.
+#[cfg(feature = "responses")]
+pub(crate) fn operation_span(operation: &'static str, model: Option<&str>) -> Span {
+    tracing::info_span!(
+        "gen_ai",
+        otel.name = match model {
+            Some(model) => format!("{operation} {model}"),
+            None => operation.to_string(),
+        },
+        otel.kind = "client",
+        otel.status_code = Empty,
+        gen_ai.operation.name = operation,
+        gen_ai.provider.name = PROVIDER,
+        gen_ai.request.model = model,
+        gen_ai.response.id = Empty,
+        gen_ai.response.model = Empty,
+        gen_ai.usage.input_tokens = Empty,
+        gen_ai.usage.output_tokens = Empty,
+        openai.request.id = Empty,
+        error.type = Empty,
+    )
+}
+
+/// Awaits an API operation inside its span and records the outcome.
+#[cfg(feature = "responses")]
+pub(crate) async fn instrument_operation<R>(
+    span: Span,
+    future: impl Future<Output = Result<crate::transport::meta::WithMeta<R>, OpenAIError>>,
+) -> Result<crate::transport::meta::WithMeta<R>, OpenAIError>
+where
+    R: Traced,
+{
+    let result = tracing::Instrument::instrument(future, span.clone()).await;
+
+    match &result {
+        Ok(response) => {
+            span.record("openai.request.id", response.meta.request_id.as_deref());
+            response.data.record(&span);
+        }
+        Err(err) => record_error(&span, err),
+    }
+
+    result
+}
+
+/// Creates the span of a single [`Transport::send`](crate::transport::Transport::send) call, covering all its attempts.
+///
+/// The requested model is recorded on the enclosing operation span only, so the body is only serialized here if bodies are traced.
+pub(crate) fn http_span<P>(
+    method: &Method,
+    url: &Url,
+    body: &RequestBody<'_, P>,
+    options: &RequestOptions,
+    trace_bodies: bool,
+) -> Span
+where
+    P: Serialize,
+{
+    let span = tracing::info_span!(
+        "http_client",
+        otel.name = method.as_str(),
+        otel.kind = "client",
+        otel.status_code = Empty,
+        http.request.method = method.as_str(),
+        http.request.resend_count = Empty,
+        http.request.body = Empty,
+        http.response.status_code = Empty,
+        http.response.body = Empty,
+        url.path = url.path(),
+        server.address = url.host_str(),
+        server.port = url.port_or_known_default(),
+        gen_ai.provider.name = PROVIDER,
+        gen_ai.response.id = Empty,
+        gen_ai.response.model = Empty,
+        gen_ai.usage.input_tokens = Empty,
+        gen_ai.usage.output_tokens = Empty,
+        openai.request.id = Empty,
+        openai.processing_ms = Empty,
+        error.type = Empty,
+    );
+
+    if trace_bodies
+        && !span.is_disabled()
+        && let Ok(Some(json)) = body.json(&options.extra_body)
+    {
+        span.record("http.request.body", json.to_string());
+    }
+
+    span
+}
+
+/// Records the attempt about to be made in the current [`http_span`].
+pub(crate) fn record_attempt(attempt: u32) {
+    if attempt > 1 {
+        Span::current().record("http.request.resend_count", attempt - 1);
+    }
+}
+
+/// Records a successful response in the current [`http_span`].
+pub(crate) fn record_response(status: StatusCode, headers: &HeaderMap) {
+    let span = Span::current();
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+    span.record("http.response.status_code", status.as_u16());
+    span.record("openai.request.id", header(REQUEST_ID));
+    span.record("openai.processing_ms", header("openai-processing-ms"));
+}
+
+/// The fields of a response body carrying gen_ai attributes.
+#[derive(Deserialize)]
+struct ResponseBody {
+    id: Option<String>,
+    model: Option<String>,
+    usage: Option<Usage>,
+}
+
+#[derive(Deserialize)]
+struct Usage {
+    input_tokens: Option<u64>,
+    output_tokens: Option<u64>,
+}
+
+impl ResponseBody {
+    fn record(self, span: &Span) {
+        span.record("gen_ai.response.id", self.id);
+        span.record("gen_ai.response.model", self.model);
+
+        if let Some(usage) = self.usage {
+            span.record("gen_ai.usage.input_tokens", usage.input_tokens);
+            span.record("gen_ai.usage.output_tokens", usage.output_tokens);
+        }
+    }
+}
+
+/// Records the gen_ai attributes found in a response body in the current [`http_span`].
+pub(crate) fn record_response_body(text: &str, trace_bodies: bool) {
+    let span = Span::current();
+    if span.is_disabled() {
+        return;
+    }
+
+    if let Ok(body) = serde_json::from_str::<ResponseBody>(text) {
+        body.record(&span);
+    }
+
+    if trace_bodies {
+        span.record("http.response.body", text);
+    }
+}
+
+/// Records a failed request. `error.type` is the status code for API errors, as the conventions suggest, and a short error kind otherwise.
+pub(crate) fn record_error(span: &Span, err: &OpenAIError) {
+    let error_type = match err {
+        OpenAIError::Api { status, .. } => status.as_str(),
+        OpenAIError::UrlParse { .. } => "url_parse",
+        OpenAIError::Env { .. } => "env",
+        OpenAIError::Config { .. } => "config",
+        #[cfg(feature = "reqwest")]
+        OpenAIError::Reqwest { .. } => "http",
+        OpenAIError::Transport { .. } => "transport",
+        OpenAIError::Credentials { .. } => "credentials",
+        OpenAIError::Timeout { .. } => "timeout",
+        OpenAIError::SerializeRequest { .. } | OpenAIError::SerializeQuery { .. } => "serialize",
+        OpenAIError::DeserializeResponse { .. } => "deserialize",
+        OpenAIError::UnexpectedContentType { .. } => "content_type",
+    };
+
+    span.record("otel.status_code", "ERROR");
+    span.record("error.type", error_type);
+
+    if let Some(meta) = err.meta() {
+        span.record("http.response.status_code", meta.status.as_u16());
+        span.record("openai.request.id", meta.request_id.as_deref());
+    }
+}
+
+/// Keeps the operation span of a streamed response open until the stream ends, recording the outcome carried by the terminal event of the response.
+///
+/// Events are recognized by their name, which the Responses API sets to the event type, or by the `type` field of unnamed events.
+#[cfg(feature = "responses-streaming")]
+#[pin_project::pin_project]
+pub(crate) struct TracedEvents {
+    #[pin]
+    inner: EventStream,
+    span: Option<Span>,
+}
+
+#[cfg(feature = "responses-streaming")]
+impl TracedEvents {
+    pub(crate) fn new(inner: EventStream, span: Span) -> Self {
+        Self {
+            inner,
+            span: Some(span),
+        }
+    }
+}
+
+#[cfg(feature = "responses-streaming")]
+impl Stream for TracedEvents {
+    type Item = Result<Event, EventStreamError<BoxError>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let item = ready!(this.inner.poll_next(cx));
+
+        if let Some(span) = this.span {
+            let ended = match &item {
+                Some(Ok(event)) => record_event(span, event),
+                Some(Err(_)) => {
+                    span.record("otel.status_code", "ERROR");
+                    span.record("error.type", "stream");
+                    true
+                }
+                None => true,
+            };
+
+            if ended {
+                *this.span = None;
+            }
+        }
+
+        Poll::Ready(item)
+    }
+}
+
+/// Records the outcome of a response if `event` ends it.
+#[cfg(feature = "responses-streaming")]
+fn record_event(span: &Span, event: &Event) -> bool {
+    #[derive(Deserialize)]
+    struct Typed {
+        r#type: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Terminal {
+        response: ResponseBody,
+    }
+
+    #[derive(Deserialize)]
+    struct Failed {
+        response: FailedResponse,
+    }
+
+    #[derive(Deserialize)]
+    struct FailedResponse {
+        error: Option<ErrorBody>,
+    }
+
+    #[derive(Deserialize)]
+    struct ErrorBody {
+        code: Option<String>,
+    }
+
+    let kind = match event.event.as_str() {
+        "message" => match serde_json::from_str::<Typed>(&event.data) {
+            Ok(typed) => typed.r#type,
+            Err(_) => return false,
+        },
+        name => name.to_string(),
+    };
+
+    match kind.as_str() {
+        "response.completed" | "response.incomplete" | "response.failed" => {
+            if let Ok(terminal) = serde_json::from_str::<Terminal>(&event.data) {
+                terminal.response.record(span);
+            }
+
+            if kind == "response.failed" {
+                let code = serde_json::from_str::<Failed>(&event.data)
+                    .ok()
+                    .and_then(|failed| failed.response.error?.code);
+
+                span.record("otel.status_code", "ERROR");
+                span.record("error.type", code.as_deref().unwrap_or("response_failed"));
+            }
+
+            true
+        }
+        "error" => {
+            let code = serde_json::from_str::<ErrorBody>(&event.data)
+                .ok()
+                .and_then(|error| error.code);
+
+            span.record("otel.status_code", "ERROR");
+            span.record("error.type", code.as_deref().unwrap_or("api"));
+            true
+        }
+        _ => false,
+    }
+}
+
+// The tests need a transport recording HTTP spans or the handlers recording operation spans.
+#[cfg(all(
+    test,
+    any(feature = "reqwest", feature = "tower", feature = "responses")
+))]
+mod test {
+    use std::{
+        collections::HashMap,
+        fmt::Debug,
+        sync::{Arc, Mutex},
+    };
+
+    use http::{Method, StatusCode};
+    use serde_json::json;
+    use tracing::{
+        Event, Metadata, Subscriber,
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+    };
+    use tracing_core::span::Current;
+
+    #[cfg(feature = "responses")]
+    use crate::transport::mock::MockTransport;
+
+    type Fields = HashMap<&'static str, String>;
+
+    /// Collects the fields of all spans, keeping track of the entered span so [`Span::current`](tracing::Span::current) works.
+    #[derive(Clone, Default)]
+    struct Capture {
+        spans: Arc<Mutex<Vec<(&'static Metadata<'static>, Fields)>>>,
+        entered: Arc<Mutex<Vec<Id>>>,
+    }
+
+    impl Capture {
+        /// Returns the fields of the spans with the given name, in the order they were created.
+        fn spans(&self, name: &str) -> Vec<Fields> {
+            self.spans
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(metadata, _)| metadata.name() == name)
+                .map(|(_, fields)| fields.clone())
+                .collect()
+        }
+    }
+
+    impl Subscriber for Capture {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut fields = Fields::new();
+            span.record(&mut Visitor(&mut fields));
+
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((span.metadata(), fields));
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            values.record(&mut Visitor(&mut spans[span.into_u64() as usize - 1].1));
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, span: &Id) {
+            self.entered.lock().unwrap().push(span.clone());
+        }
+
+        fn exit(&self, _: &Id) {
+            self.entered.lock().unwrap().pop();
+        }
+
+        fn current_span(&self) -> Current {
+            match self.entered.lock().unwrap().last() {
+                Some(id) => Current::new(
+                    id.clone(),
+                    self.spans.lock().unwrap()[id.into_u64() as usize - 1].0,
+                ),
+                None => Current::none(),
+            }
+        }
+    }
+
+    struct Visitor<'a>(&'a mut Fields);
+
+    impl Visit for Visitor<'_> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0.insert(field.name(), format!("{value:?}"));
+        }
+    }
+
+    fn response() -> serde_json::Value {
+        json!({
+            "id": "resp_123",
+            "created_at": 1741476542,
+            "model": "gpt-4o-2024-08-06",
+            "usage": {
+                "input_tokens": 5,
+                "input_tokens_details": {"cached_tokens": 0},
+                "output_tokens": 7,
+                "output_tokens_details": {"reasoning_tokens": 0},
+                "total_tokens": 12,
+            },
+        })
+    }
+
+    #[cfg(feature = "responses")]
+    #[tokio::test]
+    async fn test_operation_span() {
+        use serde_bool::False;
+
+        use crate::{OpenAI, models::responses::ResponseInput, responses::ResponseParams};
+
+        let mock = MockTransport::new();
+        mock.expect(Method::POST, "/v1/responses")
+            .reply_header(http::HeaderName::from_static("x-request-id"), "req_123")
+            .respond_json(StatusCode::OK, response());
+        mock.expect(Method::GET, "/v1/responses/resp_456")
+            .reply_header(http::HeaderName::from_static("x-request-id"), "req_456")
+            .respond_json(
+                StatusCode::NOT_FOUND,
+                json!({"error": {"message": "Not found", "type": "invalid_request_error", "param": null, "code": null}}),
+            );
+
+        let capture = Capture::default();
+        let _guard = tracing::subscriber::set_default(capture.clone());
+
+        let client = OpenAI::new(mock);
+        let params = ResponseParams {
+            input: Some(ResponseInput::Text("Hello".to_string())),
+            model: Some("gpt-4o".to_string()),
+            ..Default::default()
+        };
+        client.responses::<False>().create(&params).await.unwrap();
+        client
+            .responses::<False>()
+            .get("resp_456".into())
+            .await
+            .unwrap_err();
+
+        let spans = capture.spans("gen_ai");
+        assert_eq!(spans.len(), 2);
+
+        let created = &spans[0];
+        assert_eq!(created["otel.name"], "chat gpt-4o");
+        assert_eq!(created["gen_ai.operation.name"], "chat");
+        assert_eq!(created["gen_ai.provider.name"], "openai");
+        assert_eq!(created["gen_ai.request.model"], "gpt-4o");
+        assert_eq!(created["gen_ai.response.id"], "resp_123");
+        assert_eq!(created["gen_ai.response.model"], "gpt-4o-2024-08-06");
+        assert_eq!(created["gen_ai.usage.input_tokens"], "5");
+        assert_eq!(created["gen_ai.usage.output_tokens"], "7");
+        assert_eq!(created["openai.request.id"], "req_123");
+        assert!(!created.contains_key("error.type"));
+
+        let failed = &spans[1];
+        assert_eq!(failed["otel.name"], "get_response");
+        assert_eq!(failed["otel.status_code"], "ERROR");
+        assert_eq!(failed["error.type"], "404");
+        assert_eq!(failed["openai.request.id"], "req_456");
+        assert!(!failed.contains_key("gen_ai.response.id"));
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn test_http_span() {
+        use serde_json::Value;
+
+        use crate::transport::{
+            RequestBody, RequestOptions, StandardHttpTransport, Transport,
+            retry::RetryPolicy,
+            test::{http_response, serve},
+        };
+
+        let (address, _server) = serve(vec![
+            http_response(StatusCode::OK, "application/json", &response().to_string()),
+            http_response(
+                StatusCode::UNAUTHORIZED,
+                "application/json",
+                r#"{"error":{"message":"Invalid API key","type":"invalid_request_error","param":null,"code":"invalid_api_key"}}"#,
+            ),
+        ])
+        .await;
+
+        let transport = StandardHttpTransport::builder("sk-test".into())
+            .base_url(format!("http://{address}").parse().unwrap())
+            .retry_policy(RetryPolicy::none())
+            .trace_bodies(true)
+            .build()
+            .unwrap();
+
+        let capture = Capture::default();
+        let _guard = tracing::subscriber::set_default(capture.clone());
+
+        Transport::send::<_, Value>(
+            &transport,
+            Method::POST,
+            "/v1/responses",
+            RequestBody::Json(&json!({"model": "gpt-4o", "input": "Hello"})),
+            &RequestOptions::default(),
+        )
+        .await
+        .unwrap();
+        Transport::send::<_, Value>(
+            &transport,
+            Method::GET,
+            "/v1/responses/resp_456",
+            RequestBody::none(),
+            &RequestOptions::default(),
+        )
+        .await
+        .unwrap_err();
+
+        let spans = capture.spans("http_client");
+        assert_eq!(spans.len(), 2);
+
+        let created = &spans[0];
+        assert_eq!(created["otel.name"], "POST");
+        assert_eq!(created["http.request.method"], "POST");
+        assert_eq!(created["url.path"], "/v1/responses");
+        assert_eq!(created["server.address"], "127.0.0.1");
+        assert_eq!(created["server.port"], address.port().to_string());
+        assert_eq!(created["http.response.status_code"], "200");
+        assert_eq!(
+            created["http.request.body"],
+            r#"{"input":"Hello","model":"gpt-4o"}"#
+        );
+        assert_eq!(created["gen_ai.response.id"], "resp_123");
+        assert_eq!(created["gen_ai.usage.input_tokens"], "5");
+        assert_eq!(created["gen_ai.usage.output_tokens"], "7");
+        assert!(!created.contains_key("http.request.resend_count"));
+
+        let failed = &spans[1];
+        assert_eq!(failed["http.request.method"], "GET");
+        assert_eq!(failed["url.path"], "/v1/responses/resp_456");
+        assert_eq!(failed["otel.status_code"], "ERROR");
+        assert_eq!(failed["error.type"], "401");
+        assert_eq!(failed["http.response.status_code"], "401");
+    }
+
+    #[cfg(feature = "tower")]
+    #[tokio::test(start_paused = true)]
+    async fn test_tower_http_span() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use bytes::Bytes;
+        use serde_json::Value;
+
+        use crate::transport::{RequestBody, RequestOptions, Transport, tower::TowerTransport};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let service = tower::service_fn(move |_: http::Request<Bytes>| {
+            let calls = calls.clone();
+            async move {
+                let response = match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 => http::Response::builder()
+                        .status(StatusCode::SERVICE_UNAVAILABLE)
+                        .body(String::new()),
+                    _ => http::Response::builder()
+                        .header("x-request-id", "req_123")
+                        .body(response().to_string()),
+                };
+                Ok::<_, crate::OpenAIError>(response.unwrap())
+            }
+        });
+
+        let transport = TowerTransport::new(service)
+            .base_url("https://gateway.internal/openai".parse().unwrap())
+            .trace_bodies(true);
+
+        let capture = Capture::default();
+        let _guard = tracing::subscriber::set_default(capture.clone());
+
+        Transport::send::<_, Value>(
+            &transport,
+            Method::POST,
+            "/v1/responses",
+            RequestBody::Json(&json!({"model": "gpt-4o", "input": "Hello"})),
+            &RequestOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let spans = capture.spans("http_client");
+        assert_eq!(spans.len(), 1);
+
+        let span = &spans[0];
+        assert_eq!(span["http.request.method"], "POST");
+        assert_eq!(span["url.path"], "/openai/v1/responses");
+        assert_eq!(span["server.address"], "gateway.internal");
+        assert_eq!(span["server.port"], "443");
+        assert_eq!(span["http.request.resend_count"], "1");
+        assert_eq!(span["http.response.status_code"], "200");
+        assert_eq!(span["openai.request.id"], "req_123");
+        assert_eq!(
+            span["http.request.body"],
+            r#"{"input":"Hello","model":"gpt-4o"}"#
+        );
+        assert_eq!(span["gen_ai.response.id"], "resp_123");
+        assert_eq!(span["gen_ai.usage.output_tokens"], "7");
+    }
+
+    #[cfg(feature = "responses-streaming")]
+    #[tokio::test]
+    async fn test_stream_span() {
+        use futures::StreamExt;
+        use serde_bool::True;
+
+        use crate::{OpenAI, responses::ResponseParams};
+
+        let completed =
+            json!({"type": "response.completed", "sequence_number": 1, "response": response()});
+        let mock = MockTransport::new();
+        mock.expect(Method::POST, "/v1/responses").respond_sse(
+            StatusCode::OK,
+            format!(
+                "event: response.output_text.delta\ndata: {}\n\nevent: response.completed\ndata: {completed}\n\n",
+                json!({"type": "response.output_text.delta", "sequence_number": 0, "item_id": "msg_123", "output_index": 0, "content_index": 0, "delta": "Hi"}),
+            ),
+        );
+
+        let capture = Capture::default();
+        let _guard = tracing::subscriber::set_default(capture.clone());
+
+        let client = OpenAI::new(mock);
+        let mut stream = client
+            .responses::<True>()
+            .create(&ResponseParams::default())
+            .await
+            .unwrap();
+
+        stream.next().await.unwrap().unwrap();
+        assert!(!capture.spans("gen_ai")[0].contains_key("gen_ai.usage.input_tokens"));
+
+        while stream.next().await.is_some() {}
+
+        let span = &capture.spans("gen_ai")[0];
+        assert_eq!(span["gen_ai.response.id"], "resp_123");
+        assert_eq!(span["gen_ai.usage.input_tokens"], "5");
+        assert_eq!(span["gen_ai.usage.output_tokens"], "7");
+        assert!(!span.contains_key("error.type"));
+    }
+}
@@ -0,0 +1,438 @@
+use std::{collections::HashMap, sync::Arc};
+
+use http::Method;
+use secrecy::SecretString;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use snafu::ResultExt;
+use url::Url;
+
+use crate::{
+    EnvSnafu, OpenAIError,
+    transport::{
+        AuthScheme, RequestBody, RequestOptions, StandardHttpTransport,
+        StandardHttpTransportBuilder, Transport, credentials::CredentialProvider, meta::WithMeta,
+    },
+};
+
+const ENDPOINT_ENV: &str = "AZURE_OPENAI_ENDPOINT";
+const API_KEY_ENV: &str = "AZURE_OPENAI_API_KEY";
+const API_VERSION_ENV: &str = "OPENAI_API_VERSION";
+
+const API_VERSION_PARAM: &str = "api-version";
+
+pub const DEFAULT_API_VERSION: &str = "2025-04-01-preview";
+
+/// Credentials of an Azure OpenAI resource.
+pub enum AzureAuth {
+    /// A key of the resource, sent in the `api-key` header.
+    ApiKey(Arc<dyn CredentialProvider>),
+    /// A Microsoft Entra ID access token, sent as a bearer token. Use a [`RefreshingCredential`](crate::transport::credentials::RefreshingCredential) to renew the token before it expires.
+    EntraId(Arc<dyn CredentialProvider>),
+}
+
+impl AzureAuth {
+    pub fn api_key(credentials: impl CredentialProvider + 'static) -> Self {
+        Self::ApiKey(Arc::new(credentials))
+    }
+
+    pub fn entra_id(credentials: impl CredentialProvider + 'static) -> Self {
+        Self::EntraId(Arc::new(credentials))
+    }
+}
+
+/// How endpoint paths map to Azure OpenAI URLs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AzureApiStyle {
+    /// `/v1/responses` becomes `/openai/deployments/{deployment}/responses`, with the deployment taken from the `model` of the request. Requests without a model, such as retrieving a response, go to `/openai/responses`.
+    #[default]
+    Deployments,
+    /// `/v1/responses` becomes `/openai/v1/responses`, and the deployment is sent as the `model` of the request.
+    V1,
+}
+
+/// A [`Transport`] wrapper that talks to Azure OpenAI: it rewrites the OpenAI endpoint paths to Azure URLs, adds the mandatory `api-version` query parameter and replaces model names with deployment names.
+///
+/// The inner transport is expected to resolve paths against the resource endpoint, e.g. `https://my-resource.openai.azure.com/`, and to authenticate requests; [`AzureTransport::builder`] sets up a [`StandardHttpTransport`] doing both.
+#[derive(Clone)]
+pub struct AzureTransport<T = StandardHttpTransport> {
+    inner: T,
+    api_version: String,
+    api_style: AzureApiStyle,
+    deployments: Arc<HashMap<String, String>>,
+}
+
+/// A request rewritten for Azure OpenAI.
+struct AzureRequest {
+    path: String,
+    /// The JSON body with `extra_body` merged in and the model mapped, if the request has one.
+    body: Option<Value>,
+    options: RequestOptions,
+}
+
+impl AzureTransport<StandardHttpTransport> {
+    /// Starts building a transport for the resource at `endpoint`, e.g. `https://my-resource.openai.azure.com/`.
+    pub fn builder(endpoint: Url, auth: AzureAuth) -> AzureTransportBuilder {
+        AzureTransportBuilder::new(endpoint, auth)
+    }
+}
+
+impl<T> AzureTransport<T> {
+    /// Wraps a transport that already authenticates against the resource endpoint.
+    pub fn new(inner: T, api_version: impl Into<String>) -> Self {
+        Self {
+            inner,
+            api_version: api_version.into(),
+            api_style: AzureApiStyle::default(),
+            deployments: Default::default(),
+        }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn api_version(&self) -> &str {
+        &self.api_version
+    }
+
+    /// Returns the deployment serving `model`. Models without a configured deployment are assumed to be deployed under their own name.
+    pub fn deployment<'a>(&'a self, model: &'a str) -> &'a str {
+        self.deployments.get(model).map_or(model, String::as_str)
+    }
+
+    /// Maps an endpoint path such as `/v1/responses` to the Azure URL path.
+    fn path(&self, path: &str, deployment: Option<&str>) -> String {
+        let path = path.trim_start_matches('/');
+        let path = path.strip_prefix("v1/").unwrap_or(path);
+
+        match (self.api_style, deployment) {
+            (AzureApiStyle::V1, _) => format!("/openai/v1/{path}"),
+            (AzureApiStyle::Deployments, Some(deployment)) => {
+                format!("/openai/deployments/{deployment}/{path}")
+            }
+            (AzureApiStyle::Deployments, None) => format!("/openai/{path}"),
+        }
+    }
+
+    fn prepare<P>(
+        &self,
+        path: &str,
+        body: &RequestBody<'_, P>,
+        options: &RequestOptions,
+    ) -> Result<AzureRequest, OpenAIError>
+    where
+        P: Serialize,
+    {
+        let mut options = options.clone();
+        let mut body = body.json(&options.extra_body)?;
+
+        let deployment = match body.as_mut().and_then(|body| body.get_mut("model")) {
+            Some(Value::String(model)) => {
+                *model = self.deployment(model).to_string();
+                Some(model.clone())
+            }
+            _ => None,
+        };
+
+        if body.is_some() {
+            options.extra_body.clear();
+        }
+
+        if !options
+            .extra_query
+            .iter()
+            .any(|(key, _)| key == API_VERSION_PARAM)
+        {
+            options
+                .extra_query
+                .push((API_VERSION_PARAM.to_string(), self.api_version.clone()));
+        }
+
+        Ok(AzureRequest {
+            path: self.path(path, deployment.as_deref()),
+            body,
+            options,
+        })
+    }
+}
+
+impl<T> Transport for AzureTransport<T>
+where
+    T: Transport + Sync,
+{
+    async fn send<P, R>(
+        &self,
+        method: Method,
+        path: &str,
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
+    ) -> Result<WithMeta<R>, OpenAIError>
+    where
+        P: Sync + Serialize,
+        R: DeserializeOwned,
+    {
+        let request = self.prepare(path, &body, options)?;
+
+        match &request.body {
+            Some(json) => {
+                self.inner
+                    .send(
+                        method,
+                        &request.path,
+                        RequestBody::Json(json),
+                        &request.options,
+                    )
+                    .await
+            }
+            None => {
+                self.inner
+                    .send(method, &request.path, body, &request.options)
+                    .await
+            }
+        }
+    }
+}
+
+#[cfg(feature = "responses-streaming")]
+mod streaming {
+    use http::Method;
+    use serde::{Serialize, de::DeserializeOwned};
+
+    use crate::{
+        OpenAIError,
+        transport::{
+            RequestBody, RequestOptions,
+            azure::AzureTransport,
+            streaming::{ParsedEventStream, StreamingTransport},
+        },
+    };
+
+    impl<T> StreamingTransport for AzureTransport<T>
+    where
+        T: StreamingTransport + Sync,
+    {
+        async fn send<P, E>(
+            &self,
+            method: Method,
+            path: &str,
+            body: RequestBody<'_, P>,
+            options: &RequestOptions,
+        ) -> Result<ParsedEventStream<E>, OpenAIError>
+        where
+            P: Sync + Serialize,
+            E: Send + DeserializeOwned,
+        {
+            let request = self.prepare(path, &body, options)?;
+
+            match &request.body {
+                Some(json) => {
+                    self.inner
+                        .send(
+                            method,
+                            &request.path,
+                            RequestBody::Json(json),
+                            &request.options,
+                        )
+                        .await
+                }
+                None => {
+                    self.inner
+                        .send(method, &request.path, body, &request.options)
+                        .await
+                }
+            }
+        }
+    }
+}
+
+pub struct AzureTransportBuilder {
+    http: StandardHttpTransportBuilder,
+    api_version: String,
+    api_style: AzureApiStyle,
+    deployments: HashMap<String, String>,
+}
+
+impl AzureTransportBuilder {
+    pub fn new(endpoint: Url, auth: AzureAuth) -> Self {
+        let http = match auth {
+            AzureAuth::ApiKey(key) => {
+                StandardHttpTransport::builder_with_credentials(key).auth_scheme(AuthScheme::ApiKey)
+            }
+            AzureAuth::EntraId(token) => StandardHttpTransport::builder_with_credentials(token),
+        };
+
+        Self {
+            http: http.base_url(endpoint),
+            api_version: DEFAULT_API_VERSION.to_string(),
+            api_style: AzureApiStyle::default(),
+            deployments: HashMap::new(),
+        }
+    }
+
+    /// Reads the endpoint from `AZURE_OPENAI_ENDPOINT`, the API key from `AZURE_OPENAI_API_KEY` and, if set, the API version from `OPENAI_API_VERSION`.
+    pub fn from_env() -> Result<Self, OpenAIError> {
+        let endpoint = std::env::var(ENDPOINT_ENV).context(EnvSnafu { name: ENDPOINT_ENV })?;
+        let api_key = std::env::var(API_KEY_ENV).context(EnvSnafu { name: API_KEY_ENV })?;
+
+        let api_key = SecretString::from(api_key);
+        let mut builder = Self::new(endpoint.parse()?, AzureAuth::api_key(api_key));
+
+        if let Ok(api_version) = std::env::var(API_VERSION_ENV) {
+            builder = builder.api_version(api_version);
+        }
+
+        Ok(builder)
+    }
+
+    /// Sets the `api-version` query parameter sent with every request that doesn't set it in [`RequestOptions::extra_query`]. Default: [`DEFAULT_API_VERSION`]
+    pub fn api_version(mut self, api_version: impl Into<String>) -> Self {
+        self.api_version = api_version.into();
+        self
+    }
+
+    /// Sets how endpoint paths map to Azure URLs. Default: [`AzureApiStyle::Deployments`]
+    pub fn api_style(mut self, api_style: AzureApiStyle) -> Self {
+        self.api_style = api_style;
+        self
+    }
+
+    /// Sends requests for `model` to `deployment`.
+    pub fn deployment(mut self, model: impl Into<String>, deployment: impl Into<String>) -> Self {
+        self.deployments.insert(model.into(), deployment.into());
+        self
+    }
+
+    /// Configures the underlying HTTP transport, e.g. its client, retry policy or timeouts.
+    pub fn http(
+        mut self,
+        configure: impl FnOnce(StandardHttpTransportBuilder) -> StandardHttpTransportBuilder,
+    ) -> Self {
+        self.http = configure(self.http);
+        self
+    }
+
+    /// Builds the transport, failing if the underlying HTTP transport can't be built.
+    pub fn build(self) -> Result<AzureTransport, OpenAIError> {
+        Ok(AzureTransport {
+            inner: self.http.build()?,
+            api_version: self.api_version,
+            api_style: self.api_style,
+            deployments: Arc::new(self.deployments),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use http::{Method, StatusCode, header::HeaderName};
+    use secrecy::SecretString;
+    use serde_json::{Value, json};
+
+    use crate::transport::{
+        RequestBody, RequestOptions, Transport,
+        azure::{AzureApiStyle, AzureAuth, AzureTransport},
+        mock::MockTransport,
+    };
+
+    #[test]
+    fn test_path() {
+        let transport = AzureTransport::new((), "2025-04-01-preview");
+        assert_eq!(
+            transport.path("/v1/responses", Some("gpt-4o-prod")),
+            "/openai/deployments/gpt-4o-prod/responses"
+        );
+        assert_eq!(
+            transport.path("/v1/responses/resp_123", None),
+            "/openai/responses/resp_123"
+        );
+
+        let transport = AzureTransport {
+            api_style: AzureApiStyle::V1,
+            ..transport
+        };
+        assert_eq!(
+            transport.path("/v1/responses", Some("gpt-4o-prod")),
+            "/openai/v1/responses"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send() {
+        let mock = MockTransport::new();
+        mock.expect(Method::POST, "/openai/deployments/gpt-4o-prod/responses")
+            .with_query("api-version", "2025-04-01-preview")
+            .with_json_body(json!({"model": "gpt-4o-prod", "input": "Hello", "store": false}))
+            .respond_json(StatusCode::OK, json!({}));
+        mock.expect(Method::GET, "/openai/responses/resp_123")
+            .with_query("api-version", "preview")
+            .respond_json(StatusCode::OK, json!({}));
+
+        let mut transport = AzureTransport::new(mock.clone(), "2025-04-01-preview");
+        transport.deployments =
+            std::sync::Arc::new([("gpt-4o".to_string(), "gpt-4o-prod".to_string())].into());
+
+        let options = RequestOptions {
+            extra_body: json!({"store": false}).as_object().unwrap().clone(),
+            ..Default::default()
+        };
+        Transport::send::<_, Value>(
+            &transport,
+            Method::POST,
+            "/v1/responses",
+            RequestBody::Json(&json!({"model": "gpt-4o", "input": "Hello"})),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        let options = RequestOptions {
+            extra_query: vec![("api-version".to_string(), "preview".to_string())],
+            ..Default::default()
+        };
+        Transport::send::<_, Value>(
+            &transport,
+            Method::GET,
+            "/v1/responses/resp_123",
+            RequestBody::none(),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        mock.assert_done();
+    }
+
+    #[test]
+    fn test_api_key_header() {
+        let transport = AzureTransport::builder(
+            "https://my-resource.openai.azure.com".parse().unwrap(),
+            AzureAuth::api_key(SecretString::from("azure-key")),
+        )
+        .build()
+        .unwrap();
+
+        let request = transport
+            .inner()
+            .prepare_request(
+                Method::GET,
+                "/openai/responses/resp_123",
+                &RequestBody::none(),
+                &RequestOptions::default(),
+                &"azure-key".into(),
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            request.url().as_str(),
+            "https://my-resource.openai.azure.com/openai/responses/resp_123"
+        );
+        assert_eq!(
+            request.headers()[HeaderName::from_static("api-key")],
+            "azure-key"
+        );
+        assert!(!request.headers().contains_key("authorization"));
+    }
+}
@@ -0,0 +1,696 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use http::{
+    Method, StatusCode,
+    header::{HeaderMap, HeaderName, HeaderValue},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+use crate::{
+    OpenAIError,
+    transport::{
+        RequestBody, RequestOptions, Transport,
+        meta::{ResponseMeta, WithMeta},
+    },
+};
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Headers whose values are never written to a cassette.
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "api-key",
+    "openai-organization",
+    "openai-project",
+    "cookie",
+    "set-cookie",
+];
+
+/// A [`Transport`] wrapper that records request/response pairs to a JSON file and replays them later without network access.
+///
+/// In record mode every request is forwarded to the inner transport and the exchange, including server-sent events, is appended to the cassette. The cassette is written on [`CassetteTransport::save`] and when the last clone is dropped. In replay mode requests are answered from the cassette: each recorded interaction answers one request with the same method, path, query and JSON body, and a request without a recorded interaction panics.
+///
+/// Credentials and organization/project headers are redacted before anything is written.
+#[derive(Clone)]
+pub struct CassetteTransport<T> {
+    inner: Option<T>,
+    state: Arc<Mutex<CassetteState>>,
+}
+
+struct CassetteState {
+    path: PathBuf,
+    cassette: Cassette,
+    used: Vec<bool>,
+    recording: bool,
+    dirty: bool,
+}
+
+/// The contents of a cassette file.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub query: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// The JSON body, or a summary of multipart and raw bodies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// The response body, stored as a string if it is not JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    /// The server-sent events of a streaming response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<RecordedEvent>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RecordedEvent {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub event: String,
+    pub data: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+}
+
+impl<T> CassetteTransport<T> {
+    /// Records every request sent through `inner`, replacing the cassette at `path`.
+    pub fn record(inner: T, path: impl Into<PathBuf>) -> Self {
+        Self::new(Some(inner), path.into(), Cassette::default(), true)
+    }
+
+    /// Replays the cassette at `path`.
+    pub fn replay(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let cassette = Cassette::load(&path)?;
+        Ok(Self::new(None, path, cassette, false))
+    }
+
+    /// Replays the cassette at `path` if it exists, and records through `inner` otherwise.
+    pub fn replay_or_record(inner: T, path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+
+        if path.exists() {
+            let cassette = Cassette::load(&path)?;
+            Ok(Self::new(Some(inner), path, cassette, false))
+        } else {
+            Ok(Self::record(inner, path))
+        }
+    }
+
+    fn new(inner: Option<T>, path: PathBuf, cassette: Cassette, recording: bool) -> Self {
+        Self {
+            inner,
+            state: Arc::new(Mutex::new(CassetteState {
+                path,
+                used: vec![false; cassette.interactions.len()],
+                cassette,
+                recording,
+                dirty: false,
+            })),
+        }
+    }
+
+    /// Writes the recorded interactions to the cassette file. Does nothing in replay mode.
+    pub fn save(&self) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.recording {
+            state.cassette.save(&state.path)?;
+            state.dirty = false;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the inner transport if this transport records requests.
+    fn recorder(&self) -> Option<&T> {
+        self.inner
+            .as_ref()
+            .filter(|_| self.state.lock().unwrap().recording)
+    }
+
+    fn push(&self, request: RecordedRequest, response: RecordedResponse) -> usize {
+        let mut state = self.state.lock().unwrap();
+        state.dirty = true;
+        state.used.push(true);
+        state
+            .cassette
+            .interactions
+            .push(Interaction { request, response });
+        state.cassette.interactions.len() - 1
+    }
+
+    /// Returns the first unused interaction matching the request.
+    fn take(&self, request: &RecordedRequest) -> RecordedResponse {
+        let mut state = self.state.lock().unwrap();
+        let CassetteState {
+            path,
+            cassette,
+            used,
+            ..
+        } = &mut *state;
+
+        let Some((interaction, used)) = cassette
+            .interactions
+            .iter()
+            .zip(used.iter_mut())
+            .find(|(interaction, used)| !**used && interaction.request.matches(request))
+        else {
+            let path = path.display().to_string();
+            drop(state);
+            panic!(
+                "Cassette {path} has no recorded interaction for {} {} {}",
+                request.method,
+                request.path,
+                request
+                    .body
+                    .as_ref()
+                    .map(Value::to_string)
+                    .unwrap_or_default()
+            );
+        };
+
+        *used = true;
+        interaction.response.clone()
+    }
+}
+
+impl<T> Drop for CassetteTransport<T> {
+    fn drop(&mut self) {
+        if Arc::strong_count(&self.state) > 1 {
+            return;
+        }
+
+        let state = self.state.lock().unwrap();
+        if !state.recording || !state.dirty {
+            return;
+        }
+
+        if let Err(err) = state.cassette.save(&state.path)
+            && !std::thread::panicking()
+        {
+            panic!("Could not save cassette {}: {err}", state.path.display());
+        }
+    }
+}
+
+impl Cassette {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = std::fs::read(path)?;
+        serde_json::from_slice(&content).map_err(io::Error::other)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_vec_pretty(self).map_err(io::Error::other)?;
+        std::fs::write(path, content)
+    }
+}
+
+impl RecordedRequest {
+    fn new<P>(
+        method: &Method,
+        path: &str,
+        body: &RequestBody<'_, P>,
+        options: &RequestOptions,
+    ) -> Result<Self, OpenAIError>
+    where
+        P: Serialize,
+    {
+        let mut headers = options.extra_headers.clone();
+        if options.organization.is_some() {
+            headers.insert("openai-organization", HeaderValue::from_static(REDACTED));
+        }
+        if options.project.is_some() {
+            headers.insert("openai-project", HeaderValue::from_static(REDACTED));
+        }
+
+        let mut query = body.query()?;
+        query.extend(options.extra_query.iter().cloned());
+
+        let body = match body.json(&options.extra_body)? {
+            Some(json) => Some(json),
+            None => match body {
+                RequestBody::Multipart(form) => Some(json!({
+                    "multipart": form.parts().iter().map(|part| json!({
+                        "name": part.name,
+                        "filename": part.filename,
+                        "content_type": part.content_type,
+                        "len": part.data.len(),
+                    })).collect::<Vec<_>>()
+                })),
+                RequestBody::Raw {
+                    content_type,
+                    bytes,
+                } => Some(json!({"content_type": content_type, "len": bytes.len()})),
+                _ => None,
+            },
+        };
+
+        Ok(Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            query,
+            headers: redact(&headers),
+            body,
+        })
+    }
+
+    fn matches(&self, other: &Self) -> bool {
+        self.method == other.method
+            && self.path == other.path
+            && self.query == other.query
+            && self.body == other.body
+    }
+}
+
+impl RecordedResponse {
+    fn new(meta: &ResponseMeta, body: Option<Value>) -> Self {
+        Self {
+            status: meta.status.as_u16(),
+            headers: redact(&meta.headers),
+            body,
+            events: None,
+        }
+    }
+
+    /// Records an API error. Other errors never reached the server and are not recorded.
+    fn from_error(err: &OpenAIError) -> Option<Self> {
+        let OpenAIError::Api { text, meta, .. } = err else {
+            return None;
+        };
+
+        let body = serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.clone()));
+        Some(Self::new(meta, Some(body)))
+    }
+
+    fn meta(&self) -> ResponseMeta {
+        let headers = self
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((
+                    HeaderName::try_from(name).ok()?,
+                    HeaderValue::try_from(value).ok()?,
+                ))
+            })
+            .collect::<HeaderMap>();
+
+        ResponseMeta::new(
+            StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            headers,
+        )
+    }
+
+    fn text(&self) -> String {
+        match &self.body {
+            Some(Value::String(text)) => text.clone(),
+            Some(body) => body.to_string(),
+            None => String::new(),
+        }
+    }
+
+    /// Returns the API error this response represents, if it is not successful.
+    fn error(&self) -> Option<OpenAIError> {
+        let meta = self.meta();
+        (!meta.status.is_success()).then(|| OpenAIError::api(self.text(), meta))
+    }
+}
+
+fn redact(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if SENSITIVE_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+fn from_value<R>(data: WithMeta<Value>) -> Result<WithMeta<R>, OpenAIError>
+where
+    R: DeserializeOwned,
+{
+    match R::deserialize(&data.data) {
+        Ok(value) => Ok(WithMeta {
+            data: value,
+            meta: data.meta,
+        }),
+        Err(source) => Err(OpenAIError::DeserializeResponse {
+            source,
+            text: data.data.to_string(),
+            meta: Box::new(data.meta),
+        }),
+    }
+}
+
+impl<T> Transport for CassetteTransport<T>
+where
+    T: Transport + Sync,
+{
+    async fn send<P, R>(
+        &self,
+        method: Method,
+        path: &str,
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
+    ) -> Result<WithMeta<R>, OpenAIError>
+    where
+        P: Sync + Serialize,
+        R: DeserializeOwned,
+    {
+        let request = RecordedRequest::new(&method, path, &body, options)?;
+
+        let Some(inner) = self.recorder() else {
+            let response = self.take(&request);
+            if let Some(err) = response.error() {
+                return Err(err);
+            }
+
+            let meta = response.meta();
+            return from_value(WithMeta {
+                data: response.body.unwrap_or_default(),
+                meta,
+            });
+        };
+
+        let result = inner.send::<P, Value>(method, path, body, options).await;
+
+        let response = match &result {
+            Ok(response) => RecordedResponse::new(&response.meta, Some(response.data.clone())),
+            Err(err) => match RecordedResponse::from_error(err) {
+                Some(response) => response,
+                None => return Err(result.unwrap_err()),
+            },
+        };
+        self.push(request, response);
+
+        from_value(result?)
+    }
+}
+
+#[cfg(feature = "responses-streaming")]
+mod streaming {
+    use std::sync::Arc;
+
+    use eventsource_stream::Event;
+    use futures::{StreamExt, stream};
+    use http::Method;
+    use serde::{Serialize, de::DeserializeOwned};
+
+    use crate::{
+        OpenAIError,
+        transport::{
+            RequestBody, RequestOptions,
+            cassette::{CassetteTransport, RecordedEvent, RecordedRequest, RecordedResponse},
+            streaming::{ParsedEventStream, StreamingTransport},
+        },
+    };
+
+    impl From<&Event> for RecordedEvent {
+        fn from(event: &Event) -> Self {
+            Self {
+                event: event.event.clone(),
+                data: event.data.clone(),
+                id: event.id.clone(),
+            }
+        }
+    }
+
+    impl From<RecordedEvent> for Event {
+        fn from(event: RecordedEvent) -> Self {
+            Self {
+                event: event.event,
+                data: event.data,
+                id: event.id,
+                retry: None,
+            }
+        }
+    }
+
+    impl<T> StreamingTransport for CassetteTransport<T>
+    where
+        T: StreamingTransport + Sync,
+    {
+        async fn send<P, E>(
+            &self,
+            method: Method,
+            path: &str,
+            body: RequestBody<'_, P>,
+            options: &RequestOptions,
+        ) -> Result<ParsedEventStream<E>, OpenAIError>
+        where
+            P: Sync + Serialize,
+            E: Send + DeserializeOwned,
+        {
+            let request = RecordedRequest::new(&method, path, &body, options)?;
+
+            let Some(inner) = self.recorder() else {
+                let response = self.take(&request);
+                if let Some(err) = response.error() {
+                    return Err(err);
+                }
+
+                let meta = response.meta();
+                let events = response.events.unwrap_or_default();
+                let events = stream::iter(events.into_iter().map(|event| Ok(event.into())));
+
+                return Ok(ParsedEventStream::from_event_stream(events.boxed(), meta));
+            };
+
+            let stream = match inner.send::<P, E>(method, path, body, options).await {
+                Ok(stream) => stream,
+                Err(err) => {
+                    if let Some(response) = RecordedResponse::from_error(&err) {
+                        self.push(request, response);
+                    }
+                    return Err(err);
+                }
+            };
+
+            let (events, meta) = stream.into_event_stream();
+
+            let mut response = RecordedResponse::new(&meta, None);
+            response.events = Some(vec![]);
+            let index = self.push(request, response);
+
+            // Events are appended as the caller consumes them, so streams should be consumed before the transport is dropped.
+            let state = Arc::downgrade(&self.state);
+            let events = events.inspect(move |result| {
+                if let (Ok(event), Some(state)) = (result, state.upgrade()) {
+                    let mut state = state.lock().unwrap();
+                    state.dirty = true;
+                    state.cassette.interactions[index]
+                        .response
+                        .events
+                        .get_or_insert_default()
+                        .push(event.into());
+                }
+            });
+
+            Ok(ParsedEventStream::from_event_stream(events.boxed(), meta))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use http::{Method, StatusCode, header::HeaderName};
+    use serde_json::{Value, json};
+
+    use crate::{
+        OpenAIError,
+        transport::{
+            RequestBody, RequestOptions, Transport, cassette::CassetteTransport,
+            mock::MockTransport,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_record_and_replay() -> Result<(), OpenAIError> {
+        let path =
+            std::env::temp_dir().join(format!("openai-sdk-cassette-{}.json", std::process::id()));
+
+        let mock = MockTransport::new();
+        mock.expect(Method::POST, "/v1/things")
+            .reply_header(HeaderName::from_static("openai-organization"), "org-secret")
+            .respond_json(StatusCode::OK, json!({"id": "thing_1"}));
+        mock.expect(Method::GET, "/v1/things/thing_2").respond_json(
+            StatusCode::NOT_FOUND,
+            json!({"error": {"message": "Not found"}}),
+        );
+
+        let options = RequestOptions {
+            organization: Some("org-secret".to_string()),
+            ..Default::default()
+        };
+        let body = json!({"b": 1, "a": 2});
+
+        let recorder = CassetteTransport::record(mock, &path);
+        let recorded: Value = recorder
+            .send(
+                Method::POST,
+                "/v1/things",
+                RequestBody::Json(&body),
+                &options,
+            )
+            .await?
+            .data;
+        let recorded_err = recorder
+            .send::<(), Value>(
+                Method::GET,
+                "/v1/things/thing_2",
+                RequestBody::None,
+                &options,
+            )
+            .await
+            .unwrap_err();
+        drop(recorder);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("org-secret"));
+
+        let replayer = CassetteTransport::<MockTransport>::replay(&path).unwrap();
+        let replayed: Value = replayer
+            .send(
+                Method::POST,
+                "/v1/things",
+                RequestBody::Json(&body),
+                &options,
+            )
+            .await?
+            .data;
+        let replayed_err = replayer
+            .send::<(), Value>(
+                Method::GET,
+                "/v1/things/thing_2",
+                RequestBody::None,
+                &options,
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(recorded, replayed);
+        assert_eq!(recorded_err.api_error(), replayed_err.api_error());
+
+        std::fs::remove_file(&path).unwrap();
+
+        Ok(())
+    }
+
+    #[cfg(feature = "responses-streaming")]
+    #[tokio::test]
+    async fn test_record_and_replay_stream() -> Result<(), OpenAIError> {
+        use futures::{StreamExt, TryStreamExt};
+
+        use crate::transport::streaming::{SseEvent, StreamingTransport};
+
+        let path = std::env::temp_dir().join(format!(
+            "openai-sdk-cassette-stream-{}.json",
+            std::process::id()
+        ));
+
+        let mock = MockTransport::new();
+        mock.expect(Method::POST, "/v1/things").respond_sse(
+            StatusCode::OK,
+            "event: thing.created\nid: 1\ndata: {\"n\": 1}\n\n: keep-alive\n\nevent: thing.updated\nid: 2\ndata: {\"n\": 2}\n\ndata: {\"n\": 3}\n\n",
+        );
+
+        let body = json!({"stream": true});
+        let options = RequestOptions::default();
+
+        let recorder = CassetteTransport::record(mock, &path);
+        let recorded: Vec<_> = StreamingTransport::send::<_, Value>(
+            &recorder,
+            Method::POST,
+            "/v1/things",
+            RequestBody::Json(&body),
+            &options,
+        )
+        .await?
+        .with_sse_fields()
+        .try_collect()
+        .await
+        .unwrap();
+        drop(recorder);
+
+        let replayer = CassetteTransport::<MockTransport>::replay(&path).unwrap();
+        let replayed: Vec<_> = StreamingTransport::send::<_, Value>(
+            &replayer,
+            Method::POST,
+            "/v1/things",
+            RequestBody::Json(&body),
+            &options,
+        )
+        .await?
+        .with_sse_fields()
+        .map(Result::unwrap)
+        .collect()
+        .await;
+
+        let fields = |events: &[SseEvent<Value>]| {
+            events
+                .iter()
+                .map(|event| (event.event.clone(), event.id.clone(), event.data.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            fields(&recorded),
+            [
+                (
+                    "thing.created".to_string(),
+                    Some("1".to_string()),
+                    json!({"n": 1})
+                ),
+                (
+                    "thing.updated".to_string(),
+                    Some("2".to_string()),
+                    json!({"n": 2})
+                ),
+                (
+                    "message".to_string(),
+                    Some("2".to_string()),
+                    json!({"n": 3})
+                ),
+            ]
+        );
+        assert_eq!(fields(&replayed), fields(&recorded));
+
+        std::fs::remove_file(&path).unwrap();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+//! Sources of the access token sent with every request.
+
+use std::{
+    fs,
+    path::PathBuf,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use secrecy::SecretString;
+use snafu::ResultExt;
+use tokio::time::Instant;
+
+use crate::{CredentialsSnafu, EnvSnafu, OpenAIError, config::API_KEY_ENV, transport::BoxError};
+
+/// How long before its expiry a cached credential is refreshed.
+const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+pub type CredentialFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<SecretString, OpenAIError>> + Send + 'a>>;
+
+/// Provides the access token of a transport. It is consulted before every attempt of every request, so implementations that fetch tokens remotely should cache them.
+pub trait CredentialProvider: Send + Sync {
+    fn credential(&self) -> CredentialFuture<'_>;
+}
+
+/// A static key.
+impl CredentialProvider for SecretString {
+    fn credential(&self) -> CredentialFuture<'_> {
+        Box::pin(async { Ok(self.clone()) })
+    }
+}
+
+impl<C> CredentialProvider for Arc<C>
+where
+    C: CredentialProvider + ?Sized,
+{
+    fn credential(&self) -> CredentialFuture<'_> {
+        (**self).credential()
+    }
+}
+
+/// Reads the key from an environment variable on every request.
+#[derive(Debug, Clone)]
+pub struct EnvCredential {
+    name: &'static str,
+}
+
+impl EnvCredential {
+    pub fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+}
+
+/// Reads `OPENAI_API_KEY`.
+impl Default for EnvCredential {
+    fn default() -> Self {
+        Self::new(API_KEY_ENV)
+    }
+}
+
+impl CredentialProvider for EnvCredential {
+    fn credential(&self) -> CredentialFuture<'_> {
+        Box::pin(async {
+            let key = std::env::var(self.name).context(EnvSnafu { name: self.name })?;
+            Ok(key.into())
+        })
+    }
+}
+
+/// Reads the key from a file, such as a mounted secret, and reads it again whenever the modification time of the file changes. Surrounding whitespace is trimmed.
+///
+/// The file is checked with blocking calls, which is cheap for the small local files this is meant for.
+#[derive(Debug)]
+pub struct FileCredential {
+    path: PathBuf,
+    cached: Mutex<Option<(SystemTime, SecretString)>>,
+}
+
+impl FileCredential {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            cached: Mutex::new(None),
+        }
+    }
+
+    fn read(&self) -> Result<SecretString, BoxError> {
+        let modified = fs::metadata(&self.path)?.modified()?;
+
+        let mut cached = self.cached.lock().unwrap();
+        if let Some((cached_modified, key)) = cached.as_ref()
+            && *cached_modified == modified
+        {
+            return Ok(key.clone());
+        }
+
+        let key = SecretString::from(fs::read_to_string(&self.path)?.trim());
+        *cached = Some((modified, key.clone()));
+
+        Ok(key)
+    }
+}
+
+impl CredentialProvider for FileCredential {
+    fn credential(&self) -> CredentialFuture<'_> {
+        Box::pin(async { self.read().context(CredentialsSnafu) })
+    }
+}
+
+/// A credential that is only valid for a limited time, as returned by the refresh function of a [`RefreshingCredential`].
+#[derive(Debug, Clone)]
+pub struct ExpiringCredential {
+    pub secret: SecretString,
+    /// How long the credential stays valid. `None` if it does not expire.
+    pub expires_in: Option<Duration>,
+}
+
+/// Fetches short-lived credentials, such as Entra ID tokens or keys leased from Vault, with an async function and caches them until shortly before they expire.
+///
+/// Concurrent requests wait for a single refresh instead of each fetching a credential.
+pub struct RefreshingCredential<F> {
+    refresh: F,
+    margin: Duration,
+    cached: tokio::sync::Mutex<Option<(SecretString, Option<Instant>)>>,
+}
+
+impl<F, Fut> RefreshingCredential<F>
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = Result<ExpiringCredential, BoxError>> + Send + 'static,
+{
+    pub fn new(refresh: F) -> Self {
+        Self {
+            refresh,
+            margin: DEFAULT_REFRESH_MARGIN,
+            cached: Default::default(),
+        }
+    }
+
+    /// Sets how long before its expiry a credential is refreshed. Default: one minute
+    pub fn refresh_margin(mut self, margin: Duration) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Drops the cached credential, so the next request fetches a new one.
+    pub async fn invalidate(&self) {
+        *self.cached.lock().await = None;
+    }
+
+    async fn get(&self) -> Result<SecretString, OpenAIError> {
+        let mut cached = self.cached.lock().await;
+
+        if let Some((secret, refresh_at)) = cached.as_ref()
+            && refresh_at.is_none_or(|refresh_at| Instant::now() < refresh_at)
+        {
+            return Ok(secret.clone());
+        }
+
+        let ExpiringCredential { secret, expires_in } =
+            (self.refresh)().await.context(CredentialsSnafu)?;
+        let refresh_at =
+            expires_in.map(|expires_in| Instant::now() + expires_in.saturating_sub(self.margin));
+        *cached = Some((secret.clone(), refresh_at));
+
+        Ok(secret)
+    }
+}
+
+impl<F, Fut> CredentialProvider for RefreshingCredential<F>
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = Result<ExpiringCredential, BoxError>> + Send + 'static,
+{
+    fn credential(&self) -> CredentialFuture<'_> {
+        Box::pin(self.get())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
+
+    use secrecy::ExposeSecret;
+
+    use crate::transport::credentials::{
+        CredentialProvider, ExpiringCredential, FileCredential, RefreshingCredential,
+    };
+
+    #[tokio::test]
+    async fn test_file_credential() {
+        let path = std::env::temp_dir().join(format!("openai-sdk-key-{}", std::process::id()));
+        std::fs::write(&path, "sk-first\n").unwrap();
+
+        let credential = FileCredential::new(&path);
+        assert_eq!(
+            credential.credential().await.unwrap().expose_secret(),
+            "sk-first"
+        );
+
+        // Make sure the modification time changes even on coarse file systems.
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+        std::fs::write(&path, "sk-second").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified + Duration::from_secs(1))
+            .unwrap();
+
+        assert_eq!(
+            credential.credential().await.unwrap().expose_secret(),
+            "sk-second"
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_refreshing_credential() {
+        let refreshes = Arc::new(AtomicUsize::new(0));
+
+        let credential = RefreshingCredential::new(|| {
+            let refreshes = refreshes.clone();
+            async move {
+                let n = refreshes.fetch_add(1, Ordering::SeqCst) + 1;
+                Ok(ExpiringCredential {
+                    secret: format!("token-{n}").into(),
+                    expires_in: Some(Duration::from_secs(300)),
+                })
+            }
+        });
+
+        assert_eq!(
+            credential.credential().await.unwrap().expose_secret(),
+            "token-1"
+        );
+
+        tokio::time::advance(Duration::from_secs(200)).await;
+        assert_eq!(
+            credential.credential().await.unwrap().expose_secret(),
+            "token-1"
+        );
+
+        // Within the refresh margin of one minute
+        tokio::time::advance(Duration::from_secs(50)).await;
+        assert_eq!(
+            credential.credential().await.unwrap().expose_secret(),
+            "token-2"
+        );
+
+        credential.invalidate().await;
+        assert_eq!(
+            credential.credential().await.unwrap().expose_secret(),
+            "token-3"
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! A transport built directly on [hyper](https://docs.rs/hyper), for applications that need control over how connections are made or don't want to depend on reqwest.
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::{HeaderMap, Method};
+use http_body_util::Full;
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use hyper_util::{
+    client::legacy::{
+        Client,
+        connect::{Connect, HttpConnector},
+    },
+    rt::TokioExecutor,
+};
+use secrecy::SecretString;
+use serde::{Serialize, de::DeserializeOwned};
+use tower::{ServiceExt, util::MapRequest};
+use url::Url;
+
+use crate::{
+    OpenAIError,
+    transport::{
+        RequestBody, RequestOptions, Transport, credentials::CredentialProvider, meta::WithMeta,
+        retry::RetryPolicy, tower::TowerTransport,
+    },
+};
+
+type HyperService<C> =
+    MapRequest<Client<C, Full<Bytes>>, fn(http::Request<Bytes>) -> http::Request<Full<Bytes>>>;
+
+/// A [`Transport`] sending requests with a hyper client.
+///
+/// The connector decides how connections are established. [`HyperTransport::new`] uses HTTPS with rustls and the webpki root certificates; [`HyperTransport::with_connector`] accepts any hyper-util connector, e.g. an [`HttpsConnector`] with a custom TLS config, an [`HttpConnector`] with a custom DNS resolver, or a connector tunnelling to a sidecar proxy over a unix socket.
+///
+/// Requests are encoded like [`TowerTransport`] does, including retries, [`RequestOptions::timeout`] and, with the `tracing` feature, HTTP spans.
+#[derive(Clone)]
+pub struct HyperTransport<C = HttpsConnector<HttpConnector>> {
+    inner: TowerTransport<HyperService<C>>,
+}
+
+impl HyperTransport {
+    pub fn new() -> Self {
+        let connector = HttpsConnectorBuilder::new()
+            .with_webpki_roots()
+            .https_or_http()
+            .enable_http1()
+            .build();
+
+        Self::with_connector(connector)
+    }
+}
+
+impl Default for HyperTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> HyperTransport<C>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    /// Creates a transport making connections with `connector`.
+    pub fn with_connector(connector: C) -> Self {
+        Self::from_client(Client::builder(TokioExecutor::new()).build(connector))
+    }
+
+    /// Creates a transport sending requests with an already configured client, e.g. one with custom pool settings.
+    pub fn from_client(client: Client<C, Full<Bytes>>) -> Self {
+        let to_full: fn(http::Request<Bytes>) -> http::Request<Full<Bytes>> =
+            |request| request.map(Full::new);
+
+        Self {
+            inner: TowerTransport::new(client.map_request(to_full)),
+        }
+    }
+}
+
+impl<C> HyperTransport<C> {
+    /// Sets the URL endpoint paths are resolved against. Default: `https://api.openai.com/`
+    pub fn base_url(mut self, base_url: Url) -> Self {
+        self.inner = self.inner.base_url(base_url);
+        self
+    }
+
+    /// Sets the token sent in the `Authorization` header.
+    pub fn access_token(mut self, access_token: SecretString) -> Self {
+        self.inner = self.inner.access_token(access_token);
+        self
+    }
+
+    /// Sets the provider asked for the token sent in the `Authorization` header of every request.
+    pub fn credentials(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        self.inner = self.inner.credentials(credentials);
+        self
+    }
+
+    /// Sets the retry policy used for requests that don't override it. Default: [`RetryPolicy::default`]
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.inner = self.inner.retry_policy(retry);
+        self
+    }
+
+    /// Sets the organization requests are attributed to, sent as the `OpenAI-Organization` header.
+    pub fn organization(mut self, organization: impl Into<String>) -> Self {
+        self.inner = self.inner.organization(organization);
+        self
+    }
+
+    /// Sets the project requests are attributed to, sent as the `OpenAI-Project` header.
+    pub fn project(mut self, project: impl Into<String>) -> Self {
+        self.inner = self.inner.project(project);
+        self
+    }
+
+    /// Sets headers sent with every request.
+    pub fn default_headers(mut self, default_headers: HeaderMap) -> Self {
+        self.inner = self.inner.default_headers(default_headers);
+        self
+    }
+
+    /// Sets the total timeout of requests that don't override it. See [`RequestOptions::timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.inner = self.inner.timeout(timeout);
+        self
+    }
+
+    /// Records request and response bodies in the `http.request.body` and `http.response.body` span fields. Bodies may contain sensitive data, so this is off by default.
+    #[cfg(feature = "tracing")]
+    pub fn trace_bodies(mut self, trace_bodies: bool) -> Self {
+        self.inner = self.inner.trace_bodies(trace_bodies);
+        self
+    }
+}
+
+impl<C> Transport for HyperTransport<C>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    async fn send<P, R>(
+        &self,
+        method: Method,
+        path: &str,
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
+    ) -> Result<WithMeta<R>, OpenAIError>
+    where
+        P: Sync + Serialize,
+        R: DeserializeOwned,
+    {
+        Transport::send(&self.inner, method, path, body, options).await
+    }
+}
+
+#[cfg(feature = "responses-streaming")]
+mod streaming {
+    use http::Method;
+    use hyper_util::client::legacy::connect::Connect;
+    use serde::{Serialize, de::DeserializeOwned};
+
+    use crate::{
+        OpenAIError,
+        transport::{
+            RequestBody, RequestOptions,
+            hyper::HyperTransport,
+            streaming::{ParsedEventStream, StreamingTransport},
+        },
+    };
+
+    impl<C> StreamingTransport for HyperTransport<C>
+    where
+        C: Connect + Clone + Send + Sync + 'static,
+    {
+        async fn send<P, E>(
+            &self,
+            method: Method,
+            path: &str,
+            body: RequestBody<'_, P>,
+            options: &RequestOptions,
+        ) -> Result<ParsedEventStream<E>, OpenAIError>
+        where
+            P: Sync + Serialize,
+            E: Send + DeserializeOwned,
+        {
+            StreamingTransport::send(&self.inner, method, path, body, options).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use http::Method;
+    use serde_json::{Value, json};
+    use url::Url;
+
+    use crate::transport::{
+        RequestBody, RequestOptions, Transport, hyper::HyperTransport, test::serve,
+    };
+
+    #[tokio::test]
+    async fn test_send() {
+        let body = r#"{"id":"resp_123"}"#;
+        let (address, server) = serve(vec![format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\nx-request-id: req_123\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+            body.len()
+        )])
+        .await;
+
+        let transport = HyperTransport::new()
+            .base_url(Url::parse(&format!("http://{address}/")).unwrap())
+            .access_token("sk-test".into());
+
+        let response = Transport::send::<_, Value>(
+            &transport,
+            Method::POST,
+            "/v1/responses",
+            RequestBody::Json(&json!({ "input": "hi" })),
+            &RequestOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.data, json!({ "id": "resp_123" }));
+        assert_eq!(response.meta.request_id.as_deref(), Some("req_123"));
+
+        let request = server.await.unwrap().remove(0).to_lowercase();
+        assert!(request.starts_with("post /v1/responses http/1.1\r\n"));
+        assert!(request.contains("authorization: bearer sk-test\r\n"));
+        assert!(request.ends_with("\r\n\r\n{\"input\":\"hi\"}"));
+    }
+
+    #[cfg(feature = "responses-streaming")]
+    #[tokio::test]
+    async fn test_send_streaming() {
+        use futures::StreamExt;
+        use http::{HeaderMap, HeaderValue, StatusCode};
+
+        use crate::transport::{streaming::StreamingTransport, test::http_response};
+
+        let (address, server) = serve(vec![http_response(
+            StatusCode::OK,
+            "text/event-stream",
+            "data: {\"n\":1}\n\ndata: {\"n\":2}\n\n",
+        )])
+        .await;
+
+        let mut default_headers = HeaderMap::new();
+        default_headers.insert("x-gateway-key", HeaderValue::from_static("key_123"));
+
+        let transport = HyperTransport::new()
+            .base_url(Url::parse(&format!("http://{address}/")).unwrap())
+            .access_token("sk-test".into())
+            .organization("org_123")
+            .project("proj_123")
+            .default_headers(default_headers);
+
+        let stream = StreamingTransport::send::<_, Value>(
+            &transport,
+            Method::POST,
+            "/v1/responses",
+            RequestBody::Json(&json!({ "stream": true })),
+            &RequestOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let events: Vec<Value> = stream.map(Result::unwrap).collect().await;
+        assert_eq!(events, [json!({"n": 1}), json!({"n": 2})]);
+
+        let request = server.await.unwrap().remove(0).to_lowercase();
+        assert!(request.starts_with("post /v1/responses http/1.1\r\n"));
+        assert!(request.contains("accept: text/event-stream\r\n"));
+        assert!(request.contains("openai-organization: org_123\r\n"));
+        assert!(request.contains("openai-project: proj_123\r\n"));
+        assert!(request.contains("x-gateway-key: key_123\r\n"));
+    }
+}
@@ -0,0 +1,507 @@
+use std::{ops::ControlFlow, sync::Arc, time::Instant};
+
+use bytes::Bytes;
+use http::Method;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use snafu::ResultExt;
+
+use crate::{
+    DeserializeResponseSnafu, OpenAIError, SerializeRequestSnafu,
+    transport::{
+        RequestBody, RequestOptions, Transport,
+        meta::{ResponseMeta, WithMeta},
+        multipart::MultipartForm,
+    },
+};
+
+/// Hooks run around every request sent through an [`Intercepted`] transport.
+///
+/// Bodies are exchanged as JSON values, so interceptors can rewrite them without knowing the params and response types.
+pub trait Interceptor: Send + Sync {
+    /// Called before the request is sent, e.g. to add trace headers or rewrite the model. Breaking with a result skips the remaining interceptors and the request itself, and answers it with that result.
+    ///
+    /// For streaming requests a successful result must hold an array, whose elements are delivered as events.
+    fn before_send(
+        &self,
+        request: &mut InterceptedRequest,
+    ) -> ControlFlow<Result<WithMeta<Value>, OpenAIError>> {
+        let _ = request;
+        ControlFlow::Continue(())
+    }
+
+    /// Called with the result of the request, e.g. to log status, latency and usage. The result may be replaced. Breaking skips the remaining interceptors.
+    ///
+    /// For streaming requests this is called once the stream has started, with `null` data. Its events are passed to [`Interceptor::on_event`].
+    fn after_receive(
+        &self,
+        request: &InterceptedRequest,
+        response: &mut Result<WithMeta<Value>, OpenAIError>,
+    ) -> ControlFlow<()> {
+        let _ = (request, response);
+        ControlFlow::Continue(())
+    }
+
+    /// Called with every server-sent event of a streaming request. The event may be modified. Breaking skips the remaining interceptors.
+    #[cfg(feature = "responses-streaming")]
+    fn on_event(
+        &self,
+        request: &InterceptedRequest,
+        event: &mut eventsource_stream::Event,
+    ) -> ControlFlow<()> {
+        let _ = (request, event);
+        ControlFlow::Continue(())
+    }
+}
+
+/// A request as seen by an [`Interceptor`].
+#[derive(Debug, Clone)]
+pub struct InterceptedRequest {
+    pub method: Method,
+    pub path: String,
+    pub body: InterceptedBody,
+    pub options: RequestOptions,
+    /// Whether the response is a stream of server-sent events.
+    pub stream: bool,
+    /// When the request entered the interceptor chain, for measuring latency.
+    pub started_at: Instant,
+}
+
+/// An owned [`RequestBody`] with the params serialized to JSON.
+#[derive(Debug, Clone)]
+pub enum InterceptedBody {
+    None,
+    Json(Value),
+    Query(Value),
+    Multipart(MultipartForm),
+    Raw { content_type: String, bytes: Bytes },
+}
+
+impl InterceptedRequest {
+    fn new<P>(
+        method: Method,
+        path: &str,
+        body: &RequestBody<'_, P>,
+        options: &RequestOptions,
+        stream: bool,
+    ) -> Result<Self, OpenAIError>
+    where
+        P: Serialize,
+    {
+        let body = match body {
+            RequestBody::None => InterceptedBody::None,
+            RequestBody::Json(params) => {
+                InterceptedBody::Json(serde_json::to_value(params).context(SerializeRequestSnafu)?)
+            }
+            RequestBody::Query(params) => {
+                InterceptedBody::Query(serde_json::to_value(params).context(SerializeRequestSnafu)?)
+            }
+            RequestBody::Multipart(form) => InterceptedBody::Multipart((*form).clone()),
+            RequestBody::Raw {
+                content_type,
+                bytes,
+            } => InterceptedBody::Raw {
+                content_type: content_type.to_string(),
+                bytes: bytes.clone(),
+            },
+        };
+
+        Ok(Self {
+            method,
+            path: path.to_string(),
+            body,
+            options: options.clone(),
+            stream,
+            started_at: Instant::now(),
+        })
+    }
+
+    fn body(&self) -> RequestBody<'_, Value> {
+        match &self.body {
+            InterceptedBody::None => RequestBody::None,
+            InterceptedBody::Json(value) => RequestBody::Json(value),
+            InterceptedBody::Query(value) => RequestBody::Query(value),
+            InterceptedBody::Multipart(form) => RequestBody::Multipart(form),
+            InterceptedBody::Raw {
+                content_type,
+                bytes,
+            } => RequestBody::Raw {
+                content_type,
+                bytes: bytes.clone(),
+            },
+        }
+    }
+}
+
+/// A transport wrapper running a chain of [`Interceptor`]s around every request.
+///
+/// `before_send` hooks run in the order the interceptors were added, `after_receive` and `on_event` hooks in reverse order. Only interceptors whose `before_send` hook ran see the response.
+#[derive(Clone)]
+pub struct Intercepted<T> {
+    inner: T,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+}
+
+impl<T> Intercepted<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            interceptors: vec![],
+        }
+    }
+
+    /// Appends an interceptor to the chain.
+    pub fn with(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Runs the `before_send` hooks, returning how many ran and the short-circuit result, if any.
+    fn before_send(
+        &self,
+        request: &mut InterceptedRequest,
+    ) -> (usize, Option<Result<WithMeta<Value>, OpenAIError>>) {
+        for (index, interceptor) in self.interceptors.iter().enumerate() {
+            if let ControlFlow::Break(result) = interceptor.before_send(request) {
+                return (index + 1, Some(result));
+            }
+        }
+
+        (self.interceptors.len(), None)
+    }
+
+    fn after_receive(
+        &self,
+        ran: usize,
+        request: &InterceptedRequest,
+        response: &mut Result<WithMeta<Value>, OpenAIError>,
+    ) {
+        for interceptor in self.interceptors[..ran].iter().rev() {
+            if interceptor.after_receive(request, response).is_break() {
+                break;
+            }
+        }
+    }
+}
+
+impl<T> Transport for Intercepted<T>
+where
+    T: Transport + Sync,
+{
+    async fn send<P, R>(
+        &self,
+        method: Method,
+        path: &str,
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
+    ) -> Result<WithMeta<R>, OpenAIError>
+    where
+        P: Sync + Serialize,
+        R: DeserializeOwned,
+    {
+        let mut request = InterceptedRequest::new(method, path, &body, options, false)?;
+
+        let (ran, response) = self.before_send(&mut request);
+        let mut response = match response {
+            Some(response) => response,
+            None => {
+                self.inner
+                    .send(
+                        request.method.clone(),
+                        &request.path,
+                        request.body(),
+                        &request.options,
+                    )
+                    .await
+            }
+        };
+
+        self.after_receive(ran, &request, &mut response);
+
+        let WithMeta { data, meta } = response?;
+        match R::deserialize(&data) {
+            Ok(data) => Ok(WithMeta { data, meta }),
+            Err(source) => Err(source).context(DeserializeResponseSnafu {
+                text: data.to_string(),
+                meta: Box::new(meta),
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "responses-streaming")]
+mod streaming {
+    use std::sync::Arc;
+
+    use eventsource_stream::Event;
+    use futures::{StreamExt, stream};
+    use http::Method;
+    use serde::{Serialize, de::DeserializeOwned};
+    use serde_json::Value;
+
+    use crate::{
+        OpenAIError,
+        transport::{
+            RequestBody, RequestOptions,
+            interceptor::{Intercepted, InterceptedRequest},
+            meta::WithMeta,
+            streaming::{EventStream, ParsedEventStream, StreamingTransport},
+        },
+    };
+
+    impl<T> StreamingTransport for Intercepted<T>
+    where
+        T: StreamingTransport + Sync,
+    {
+        async fn send<P, E>(
+            &self,
+            method: Method,
+            path: &str,
+            body: RequestBody<'_, P>,
+            options: &RequestOptions,
+        ) -> Result<ParsedEventStream<E>, OpenAIError>
+        where
+            P: Sync + Serialize,
+            E: Send + DeserializeOwned,
+        {
+            let mut request = InterceptedRequest::new(method, path, &body, options, true)?;
+
+            let (ran, response) = self.before_send(&mut request);
+            let (mut response, events): (_, EventStream) = match response {
+                Some(response) => {
+                    let events = match &response {
+                        Ok(WithMeta {
+                            data: Value::Array(events),
+                            ..
+                        }) => events.clone(),
+                        _ => vec![],
+                    };
+                    let events = events.into_iter().map(|data| {
+                        Ok(Event {
+                            data: data.to_string(),
+                            ..Default::default()
+                        })
+                    });
+                    (response, stream::iter(events).boxed())
+                }
+                None => match StreamingTransport::send::<_, Value>(
+                    &self.inner,
+                    request.method.clone(),
+                    &request.path,
+                    request.body(),
+                    &request.options,
+                )
+                .await
+                {
+                    Ok(stream) => {
+                        let (events, meta) = stream.into_event_stream();
+                        let response = WithMeta {
+                            data: Value::Null,
+                            meta,
+                        };
+                        (Ok(response), events)
+                    }
+                    Err(err) => (Err(err), stream::empty().boxed()),
+                },
+            };
+
+            self.after_receive(ran, &request, &mut response);
+            let meta = response?.meta;
+
+            let interceptors = self.interceptors[..ran].to_vec();
+            let request = Arc::new(request);
+            let events = events.map(move |mut result| {
+                if let Ok(event) = &mut result {
+                    for interceptor in interceptors.iter().rev() {
+                        if interceptor.on_event(&request, event).is_break() {
+                            break;
+                        }
+                    }
+                }
+                result
+            });
+
+            Ok(ParsedEventStream::from_event_stream(events.boxed(), meta))
+        }
+    }
+}
+
+/// Builds a value handed to [`Interceptor::before_send`] as a short-circuit response, e.g. to reply from a cache.
+pub fn respond<T>(data: T, meta: ResponseMeta) -> Result<WithMeta<Value>, OpenAIError>
+where
+    T: Serialize,
+{
+    let data = serde_json::to_value(data).context(SerializeRequestSnafu)?;
+    Ok(WithMeta { data, meta })
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        ops::ControlFlow,
+        sync::{Arc, Mutex},
+    };
+
+    use http::{Method, StatusCode, header::HeaderValue};
+    use serde::Deserialize;
+    use serde_json::{Value, json};
+
+    use crate::{
+        OpenAIError,
+        transport::{
+            RequestBody, RequestOptions, Transport,
+            interceptor::{Intercepted, InterceptedBody, InterceptedRequest, Interceptor, respond},
+            meta::{ResponseMeta, WithMeta},
+            mock::MockTransport,
+        },
+    };
+
+    struct Tenant;
+
+    impl Interceptor for Tenant {
+        fn before_send(
+            &self,
+            request: &mut InterceptedRequest,
+        ) -> ControlFlow<Result<WithMeta<Value>, OpenAIError>> {
+            if let InterceptedBody::Json(body) = &mut request.body {
+                body["model"] = json!("gpt-4o-mini");
+            }
+            request
+                .options
+                .extra_headers
+                .insert("traceparent", HeaderValue::from_static("00-abc-def-01"));
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[derive(Default)]
+    struct Log(Mutex<Vec<StatusCode>>);
+
+    impl Interceptor for Arc<Log> {
+        fn after_receive(
+            &self,
+            _request: &InterceptedRequest,
+            response: &mut Result<WithMeta<Value>, OpenAIError>,
+        ) -> ControlFlow<()> {
+            let status = match response {
+                Ok(response) => response.meta.status,
+                Err(err) => err.meta().unwrap().status,
+            };
+            self.0.lock().unwrap().push(status);
+            ControlFlow::Continue(())
+        }
+    }
+
+    struct Cache;
+
+    impl Interceptor for Cache {
+        fn before_send(
+            &self,
+            request: &mut InterceptedRequest,
+        ) -> ControlFlow<Result<WithMeta<Value>, OpenAIError>> {
+            match request.method {
+                Method::GET => ControlFlow::Break(respond(
+                    json!({ "id": "resp_cached" }),
+                    ResponseMeta::default(),
+                )),
+                _ => ControlFlow::Continue(()),
+            }
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Response {
+        id: String,
+    }
+
+    #[tokio::test]
+    async fn test_interceptors() {
+        let mock = MockTransport::new();
+        mock.expect(Method::POST, "/v1/responses")
+            .with_json_body(json!({ "model": "gpt-4o-mini", "input": "hi" }))
+            .with_header("traceparent".parse().unwrap(), "00-abc-def-01")
+            .respond_json(StatusCode::OK, json!({ "id": "resp_123" }));
+
+        let log = Arc::new(Log::default());
+        let transport = Intercepted::new(mock.clone())
+            .with(Tenant)
+            .with(log.clone())
+            .with(Cache);
+
+        let response = Transport::send::<_, Response>(
+            &transport,
+            Method::POST,
+            "/v1/responses",
+            RequestBody::Json(&json!({ "model": "gpt-4o", "input": "hi" })),
+            &RequestOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.data.id, "resp_123");
+
+        let response = Transport::send::<(), Response>(
+            &transport,
+            Method::GET,
+            "/v1/responses/resp_123",
+            RequestBody::none(),
+            &RequestOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.data.id, "resp_cached");
+
+        assert_eq!(*log.0.lock().unwrap(), [StatusCode::OK, StatusCode::OK]);
+        mock.assert_done();
+    }
+
+    #[cfg(feature = "responses-streaming")]
+    #[tokio::test]
+    async fn test_stream_interceptor() {
+        use futures::TryStreamExt;
+
+        use crate::transport::streaming::StreamingTransport;
+
+        struct Redact;
+
+        impl Interceptor for Redact {
+            fn on_event(
+                &self,
+                _request: &InterceptedRequest,
+                event: &mut eventsource_stream::Event,
+            ) -> ControlFlow<()> {
+                event.data = event.data.replace("secret", "[redacted]");
+                ControlFlow::Continue(())
+            }
+        }
+
+        let mock = MockTransport::new();
+        mock.expect(Method::POST, "/v1/responses").respond_sse(
+            StatusCode::OK,
+            "data: {\"delta\":\"a secret\"}\n\ndata: {\"delta\":\"!\"}\n\n",
+        );
+
+        let transport = Intercepted::new(mock).with(Redact);
+
+        let events: Vec<Value> = StreamingTransport::send::<_, Value>(
+            &transport,
+            Method::POST,
+            "/v1/responses",
+            RequestBody::Json(&json!({ "stream": true })),
+            &RequestOptions::default(),
+        )
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(
+            events,
+            [json!({ "delta": "a [redacted]" }), json!({ "delta": "!" })]
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use http::{StatusCode, header::HeaderMap};
+
+use crate::transport::retry::parse_reset_duration;
+
+const REQUEST_ID: &str = "x-request-id";
+const PROCESSING_MS: &str = "openai-processing-ms";
+const RATELIMIT_LIMIT_REQUESTS: &str = "x-ratelimit-limit-requests";
+const RATELIMIT_LIMIT_TOKENS: &str = "x-ratelimit-limit-tokens";
+const RATELIMIT_REMAINING_REQUESTS: &str = "x-ratelimit-remaining-requests";
+const RATELIMIT_REMAINING_TOKENS: &str = "x-ratelimit-remaining-tokens";
+const RATELIMIT_RESET_REQUESTS: &str = "x-ratelimit-reset-requests";
+const RATELIMIT_RESET_TOKENS: &str = "x-ratelimit-reset-tokens";
+
+/// HTTP-level information about a response.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseMeta {
+    pub status: StatusCode,
+    /// Value of the `x-request-id` header. Include it when contacting support.
+    pub request_id: Option<String>,
+    /// Time the API spent processing the request, from the `openai-processing-ms` header.
+    pub processing_time: Option<Duration>,
+    pub rate_limit: RateLimitInfo,
+    /// All response headers.
+    pub headers: HeaderMap,
+}
+
+impl ResponseMeta {
+    pub fn new(status: StatusCode, headers: HeaderMap) -> Self {
+        let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+        Self {
+            status,
+            request_id: header(REQUEST_ID).map(str::to_string),
+            processing_time: header(PROCESSING_MS)
+                .and_then(|value| value.parse::<f64>().ok())
+                .and_then(|ms| Duration::try_from_secs_f64(ms / 1000.0).ok()),
+            rate_limit: RateLimitInfo::from_headers(&headers),
+            headers,
+        }
+    }
+}
+
+/// Rate limit state reported by the `x-ratelimit-*` headers.
+///
+/// https://platform.openai.com/docs/guides/rate-limits#rate-limits-in-headers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    /// The maximum number of requests permitted before exhausting the rate limit.
+    pub limit_requests: Option<u64>,
+    /// The maximum number of tokens permitted before exhausting the rate limit.
+    pub limit_tokens: Option<u64>,
+    /// The remaining number of requests permitted before exhausting the rate limit.
+    pub remaining_requests: Option<u64>,
+    /// The remaining number of tokens permitted before exhausting the rate limit.
+    pub remaining_tokens: Option<u64>,
+    /// The time until the request rate limit resets to its initial state.
+    pub reset_requests: Option<Duration>,
+    /// The time until the token rate limit resets to its initial state.
+    pub reset_tokens: Option<Duration>,
+}
+
+impl RateLimitInfo {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+        let number = |name| header(name).and_then(|value| value.parse().ok());
+        let duration = |name| header(name).and_then(parse_reset_duration);
+
+        Self {
+            limit_requests: number(RATELIMIT_LIMIT_REQUESTS),
+            limit_tokens: number(RATELIMIT_LIMIT_TOKENS),
+            remaining_requests: number(RATELIMIT_REMAINING_REQUESTS),
+            remaining_tokens: number(RATELIMIT_REMAINING_TOKENS),
+            reset_requests: duration(RATELIMIT_RESET_REQUESTS),
+            reset_tokens: duration(RATELIMIT_RESET_TOKENS),
+        }
+    }
+}
+
+/// A result together with the metadata of the response it was read from.
+#[derive(Debug, Clone)]
+pub struct WithMeta<T> {
+    pub data: T,
+    pub meta: ResponseMeta,
+}
+
+impl<T> WithMeta<T> {
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> WithMeta<U> {
+        WithMeta {
+            data: f(self.data),
+            meta: self.meta,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use http::{
+        StatusCode,
+        header::{HeaderMap, HeaderValue},
+    };
+
+    use crate::transport::meta::{RateLimitInfo, ResponseMeta};
+
+    #[test]
+    fn test_response_meta() {
+        let mut headers = HeaderMap::new();
+        for (name, value) in [
+            ("x-request-id", "req_123"),
+            ("openai-processing-ms", "250"),
+            ("x-ratelimit-limit-requests", "60"),
+            ("x-ratelimit-limit-tokens", "150000"),
+            ("x-ratelimit-remaining-requests", "59"),
+            ("x-ratelimit-remaining-tokens", "149984"),
+            ("x-ratelimit-reset-requests", "1s"),
+            ("x-ratelimit-reset-tokens", "6m0s"),
+        ] {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+
+        let meta = ResponseMeta::new(StatusCode::OK, headers);
+
+        assert_eq!(meta.request_id.as_deref(), Some("req_123"));
+        assert_eq!(meta.processing_time, Some(Duration::from_millis(250)));
+        assert_eq!(
+            meta.rate_limit,
+            RateLimitInfo {
+                limit_requests: Some(60),
+                limit_tokens: Some(150000),
+                remaining_requests: Some(59),
+                remaining_tokens: Some(149984),
+                reset_requests: Some(Duration::from_secs(1)),
+                reset_tokens: Some(Duration::from_secs(360)),
+            }
+        );
+    }
+}
@@ -0,0 +1,546 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+use http::{
+    Method, StatusCode,
+    header::{self, HeaderMap, HeaderName, HeaderValue},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::{
+    OpenAIError,
+    transport::{
+        RequestBody, RequestOptions, Transport, deserialize_response,
+        meta::{ResponseMeta, WithMeta},
+        multipart::MultipartForm,
+    },
+};
+
+/// An in-process [`Transport`] answering requests from scripted expectations, for testing code that uses [`OpenAI`](crate::OpenAI) without network access.
+///
+/// Each request is matched against the registered expectations in order; the first one that matches and has remaining uses answers it. A request that matches no expectation panics. Clones share their expectations, and dropping the last clone while expectations are left over panics as well, unless [`MockTransport::assert_done`] was already called.
+#[derive(Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    expectations: Vec<Expectation>,
+    requests: Vec<MockRequest>,
+}
+
+type Matcher = Box<dyn Fn(&MockRequest) -> bool + Send>;
+
+struct Expectation {
+    method: Method,
+    path: String,
+    matchers: Vec<Matcher>,
+    reply: MockReply,
+    remaining: usize,
+}
+
+impl fmt::Display for Expectation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.path)
+    }
+}
+
+/// A request received by a [`MockTransport`], after applying the [`RequestOptions`].
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub method: Method,
+    pub path: String,
+    pub headers: HeaderMap,
+    pub query: Vec<(String, String)>,
+    pub body: MockBody,
+}
+
+#[derive(Debug, Clone)]
+pub enum MockBody {
+    None,
+    Json(Value),
+    Multipart(MultipartForm),
+    Raw { content_type: String, bytes: Bytes },
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MockReply {
+    pub(crate) status: StatusCode,
+    pub(crate) headers: HeaderMap,
+    pub(crate) body: Bytes,
+}
+
+impl MockReply {
+    fn meta(&self) -> ResponseMeta {
+        ResponseMeta::new(self.status, self.headers.clone())
+    }
+
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// Builder for an expectation, registered once a `respond_*` method is called.
+#[must_use = "expectations are only registered by calling a `respond_*` method"]
+pub struct ExpectationBuilder<'a> {
+    transport: &'a MockTransport,
+    method: Method,
+    path: String,
+    matchers: Vec<Matcher>,
+    headers: HeaderMap,
+    times: usize,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expects a request with the given method and path, e.g. `/v1/responses`.
+    pub fn expect(&self, method: Method, path: impl Into<String>) -> ExpectationBuilder<'_> {
+        ExpectationBuilder {
+            transport: self,
+            method,
+            path: path.into(),
+            matchers: vec![],
+            headers: HeaderMap::new(),
+            times: 1,
+        }
+    }
+
+    /// Returns all requests received so far.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Panics if any expectation has remaining uses.
+    pub fn assert_done(&self) {
+        let mut state = self.state.lock().unwrap();
+        let leftover = leftover(&state.expectations);
+        state.expectations.clear();
+
+        if !leftover.is_empty() {
+            panic!("MockTransport has unmatched expectations: {leftover}");
+        }
+    }
+
+    /// Records the request and returns the reply of the first matching expectation.
+    pub(crate) fn handle<P>(
+        &self,
+        method: Method,
+        path: &str,
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
+    ) -> Result<MockReply, OpenAIError>
+    where
+        P: Serialize,
+    {
+        let request = MockRequest::new(method, path, body, options)?;
+
+        let mut state = self.state.lock().unwrap();
+        state.requests.push(request.clone());
+
+        let Some(expectation) = state.expectations.iter_mut().find(|expectation| {
+            expectation.remaining > 0
+                && expectation.method == request.method
+                && expectation.path == request.path
+                && expectation.matchers.iter().all(|matcher| matcher(&request))
+        }) else {
+            let leftover = leftover(&state.expectations);
+            drop(state);
+            panic!(
+                "MockTransport received an unexpected request: {} {} {:?}\nRemaining expectations: {leftover}",
+                request.method, request.path, request.body
+            );
+        };
+
+        expectation.remaining -= 1;
+        Ok(expectation.reply.clone())
+    }
+}
+
+impl Drop for MockTransport {
+    fn drop(&mut self) {
+        if Arc::strong_count(&self.state) > 1 || std::thread::panicking() {
+            return;
+        }
+
+        let leftover = leftover(&self.state.lock().unwrap().expectations);
+        if !leftover.is_empty() {
+            panic!("MockTransport dropped with unmatched expectations: {leftover}");
+        }
+    }
+}
+
+fn leftover(expectations: &[Expectation]) -> String {
+    expectations
+        .iter()
+        .filter(|expectation| expectation.remaining > 0)
+        .map(Expectation::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl MockRequest {
+    fn new<P>(
+        method: Method,
+        path: &str,
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
+    ) -> Result<Self, OpenAIError>
+    where
+        P: Serialize,
+    {
+        let mut headers = HeaderMap::new();
+        if let Some(organization) = &options.organization {
+            headers.insert("openai-organization", header_value(organization));
+        }
+        if let Some(project) = &options.project {
+            headers.insert("openai-project", header_value(project));
+        }
+        headers.extend(options.extra_headers.clone());
+
+        let mut query = body.query()?;
+        query.extend(options.extra_query.iter().cloned());
+
+        let body = match body.json(&options.extra_body)? {
+            Some(json) => MockBody::Json(json),
+            None => match body {
+                RequestBody::Multipart(form) => MockBody::Multipart(form.clone()),
+                RequestBody::Raw {
+                    content_type,
+                    bytes,
+                } => MockBody::Raw {
+                    content_type: content_type.to_string(),
+                    bytes,
+                },
+                _ => MockBody::None,
+            },
+        };
+
+        Ok(Self {
+            method,
+            path: path.to_string(),
+            headers,
+            query,
+            body,
+        })
+    }
+
+    /// Returns the JSON body, if the request has one.
+    pub fn json(&self) -> Option<&Value> {
+        match &self.body {
+            MockBody::Json(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+fn header_value(value: &str) -> HeaderValue {
+    HeaderValue::from_str(value).expect("invalid header value")
+}
+
+impl ExpectationBuilder<'_> {
+    /// Only matches requests whose JSON body equals `body`.
+    pub fn with_json_body(self, body: Value) -> Self {
+        self.matching(move |request| request.json() == Some(&body))
+    }
+
+    /// Only matches requests with the given query parameter.
+    pub fn with_query(self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let pair = (key.into(), value.into());
+        self.matching(move |request| request.query.contains(&pair))
+    }
+
+    /// Only matches requests with the given header.
+    pub fn with_header(self, name: HeaderName, value: impl Into<String>) -> Self {
+        let value = value.into();
+        self.matching(move |request| {
+            request
+                .headers
+                .get(&name)
+                .is_some_and(|header| header.as_bytes() == value.as_bytes())
+        })
+    }
+
+    /// Only matches requests accepted by `matcher`.
+    pub fn matching(mut self, matcher: impl Fn(&MockRequest) -> bool + Send + 'static) -> Self {
+        self.matchers.push(Box::new(matcher));
+        self
+    }
+
+    /// Adds a header to the reply, e.g. `x-request-id`.
+    pub fn reply_header(mut self, name: HeaderName, value: impl Into<String>) -> Self {
+        self.headers.insert(name, header_value(&value.into()));
+        self
+    }
+
+    /// Allows the expectation to match `times` requests instead of one.
+    pub fn times(mut self, times: usize) -> Self {
+        self.times = times;
+        self
+    }
+
+    /// Replies with a JSON body.
+    pub fn respond_json(self, status: StatusCode, body: impl Serialize) {
+        let body = serde_json::to_vec(&body).expect("could not serialize mock reply");
+        self.respond(status, "application/json", body.into());
+    }
+
+    /// Replies with an empty body.
+    pub fn respond_empty(self, status: StatusCode) {
+        self.respond(status, "application/json", Bytes::new());
+    }
+
+    /// Replies with a `text/event-stream` body in SSE format, e.g. `"data: {}\n\n"`.
+    pub fn respond_sse(self, status: StatusCode, body: impl Into<String>) {
+        self.respond(status, "text/event-stream", Bytes::from(body.into()));
+    }
+
+    fn respond(mut self, status: StatusCode, content_type: &'static str, body: Bytes) {
+        self.headers
+            .entry(header::CONTENT_TYPE)
+            .or_insert(HeaderValue::from_static(content_type));
+
+        self.transport
+            .state
+            .lock()
+            .unwrap()
+            .expectations
+            .push(Expectation {
+                method: self.method,
+                path: self.path,
+                matchers: self.matchers,
+                reply: MockReply {
+                    status,
+                    headers: self.headers,
+                    body,
+                },
+                remaining: self.times,
+            });
+    }
+}
+
+impl Transport for MockTransport {
+    async fn send<P, R>(
+        &self,
+        method: Method,
+        path: &str,
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
+    ) -> Result<WithMeta<R>, OpenAIError>
+    where
+        P: Sync + Serialize,
+        R: DeserializeOwned,
+    {
+        let reply = self.handle(method, path, body, options)?;
+
+        if reply.status.is_success() {
+            deserialize_response(reply.text(), reply.meta())
+        } else {
+            Err(OpenAIError::api(reply.text(), reply.meta()))
+        }
+    }
+}
+
+#[cfg(feature = "responses-streaming")]
+mod streaming {
+    use futures::{StreamExt, stream};
+    use http::Method;
+    use serde::{Serialize, de::DeserializeOwned};
+
+    use crate::{
+        OpenAIError,
+        transport::{
+            RequestBody, RequestOptions,
+            mock::MockTransport,
+            streaming::{
+                ParsedEventStream, StreamingTransport, is_event_stream, unexpected_content_type,
+            },
+        },
+    };
+
+    impl StreamingTransport for MockTransport {
+        async fn send<P, E>(
+            &self,
+            method: Method,
+            path: &str,
+            body: RequestBody<'_, P>,
+            options: &RequestOptions,
+        ) -> Result<ParsedEventStream<E>, OpenAIError>
+        where
+            P: Sync + Serialize,
+            E: Send + DeserializeOwned,
+        {
+            let reply = self.handle(method, path, body, options)?;
+
+            if !reply.status.is_success() {
+                return Err(OpenAIError::api(reply.text(), reply.meta()));
+            }
+
+            let meta = reply.meta();
+            if !is_event_stream(&meta) {
+                return Err(unexpected_content_type(reply.text(), meta));
+            }
+
+            let bytes = stream::iter([Ok(reply.body)]).boxed();
+            let stream = ParsedEventStream::from_bytes_stream(bytes, meta);
+
+            Ok(match options.stream_idle_timeout {
+                Some(timeout) => stream.with_idle_timeout(timeout),
+                None => stream,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use http::{Method, StatusCode, header::HeaderName};
+    use serde_json::{Value, json};
+
+    use crate::{
+        OpenAIError,
+        transport::{RequestBody, RequestOptions, Transport, mock::MockTransport},
+    };
+
+    #[tokio::test]
+    async fn test_send() -> Result<(), OpenAIError> {
+        let mock = MockTransport::new();
+        mock.expect(Method::POST, "/v1/things")
+            .with_json_body(json!({"name": "a", "extra": true}))
+            .reply_header(HeaderName::from_static("x-request-id"), "req_1")
+            .respond_json(StatusCode::OK, json!({"id": "thing_1"}));
+        mock.expect(Method::GET, "/v1/things/thing_1").respond_json(
+            StatusCode::NOT_FOUND,
+            json!({"error": {"message": "Not found"}}),
+        );
+
+        let options = RequestOptions {
+            extra_body: json!({"extra": true}).as_object().unwrap().clone(),
+            ..Default::default()
+        };
+        let response: Value = Transport::send(
+            &mock,
+            Method::POST,
+            "/v1/things",
+            RequestBody::Json(&json!({"name": "a"})),
+            &options,
+        )
+        .await?
+        .data;
+        assert_eq!(response, json!({"id": "thing_1"}));
+
+        let err = Transport::send::<(), Value>(
+            &mock,
+            Method::GET,
+            "/v1/things/thing_1",
+            RequestBody::None,
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.api_error().unwrap().message, "Not found");
+
+        assert_eq!(mock.requests().len(), 2);
+        mock.assert_done();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "unexpected request: GET /v1/things")]
+    async fn test_unexpected_request() {
+        let mock = MockTransport::new();
+
+        let _ = Transport::send::<(), Value>(
+            &mock,
+            Method::GET,
+            "/v1/things",
+            RequestBody::None,
+            &Default::default(),
+        )
+        .await;
+    }
+
+    #[test]
+    #[should_panic(expected = "unmatched expectations: GET /v1/things")]
+    fn test_leftover_expectation() {
+        let mock = MockTransport::new();
+        mock.expect(Method::GET, "/v1/things")
+            .respond_empty(StatusCode::NO_CONTENT);
+    }
+
+    #[cfg(feature = "responses-streaming")]
+    #[tokio::test]
+    async fn test_send_streaming() -> Result<(), OpenAIError> {
+        use futures::StreamExt;
+
+        use crate::transport::streaming::StreamingTransport;
+
+        let mock = MockTransport::new();
+        mock.expect(Method::POST, "/v1/things").respond_sse(
+            StatusCode::OK,
+            "event: thing\ndata: {\"n\": 1}\n\nevent: thing\ndata: {\"n\": 2}\n\n",
+        );
+
+        let stream = StreamingTransport::send::<_, Value>(
+            &mock,
+            Method::POST,
+            "/v1/things",
+            RequestBody::Json(&json!({"stream": true})),
+            &RequestOptions::default(),
+        )
+        .await?;
+
+        let events: Vec<_> = stream.map(Result::unwrap).collect().await;
+        assert_eq!(events, [json!({"n": 1}), json!({"n": 2})]);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "responses-streaming")]
+    #[tokio::test]
+    async fn test_send_streaming_not_an_event_stream() {
+        use crate::transport::streaming::StreamingTransport;
+
+        let mock = MockTransport::new();
+        mock.expect(Method::POST, "/v1/things")
+            .respond_json(StatusCode::OK, json!({"id": "thing_1"}));
+        mock.expect(Method::POST, "/v1/things").respond_json(
+            StatusCode::TOO_MANY_REQUESTS,
+            json!({"error": {"message": "Slow down"}}),
+        );
+
+        let body = json!({"stream": true});
+        let options = RequestOptions::default();
+        let send = || {
+            StreamingTransport::send::<_, Value>(
+                &mock,
+                Method::POST,
+                "/v1/things",
+                RequestBody::Json(&body),
+                &options,
+            )
+        };
+
+        let Err(OpenAIError::UnexpectedContentType {
+            content_type, text, ..
+        }) = send().await
+        else {
+            panic!("expected an unexpected content type error");
+        };
+        assert_eq!(content_type.as_deref(), Some("application/json"));
+        assert_eq!(text, r#"{"id":"thing_1"}"#);
+
+        let Err(err) = send().await else {
+            panic!("expected an API error");
+        };
+        assert_eq!(err.api_error().unwrap().message, "Slow down");
+    }
+}
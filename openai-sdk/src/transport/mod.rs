@@ -1,93 +1,991 @@
-use std::sync::LazyLock;
+use std::time::Duration;
+#[cfg(feature = "reqwest")]
+use std::{mem, sync::Arc};
 
-use reqwest::{Method, RequestBuilder, Url};
+use bytes::Bytes;
+#[cfg(feature = "reqwest")]
+use http::header::{self, HeaderValue};
+use http::{Method, header::HeaderMap};
+#[cfg(feature = "reqwest")]
+pub use reqwest::Proxy;
+#[cfg(feature = "__tls")]
+pub use reqwest::{Certificate, Identity};
+#[cfg(feature = "reqwest")]
+use reqwest::{RequestBuilder, Response};
+#[cfg(feature = "reqwest")]
 use secrecy::{ExposeSecret, SecretString};
 use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
 use snafu::ResultExt;
+#[cfg(any(feature = "reqwest", feature = "tower"))]
+use url::Url;
 
-use crate::{DeserializeResponseSnafu, OpenAIError};
+#[cfg(all(feature = "reqwest", feature = "tracing"))]
+use crate::telemetry;
+use crate::{
+    DeserializeResponseSnafu, OpenAIError, SerializeQuerySnafu, SerializeRequestSnafu,
+    transport::{
+        meta::{ResponseMeta, WithMeta},
+        multipart::MultipartForm,
+        retry::RetryPolicy,
+    },
+};
+#[cfg(feature = "reqwest")]
+use crate::{config::ClientConfig, transport::credentials::CredentialProvider};
 
+#[cfg(feature = "reqwest")]
+pub mod azure;
+#[cfg(feature = "cassette")]
+pub mod cassette;
+pub mod credentials;
+#[cfg(feature = "hyper")]
+pub mod hyper;
+pub mod interceptor;
+pub mod meta;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod multipart;
+pub mod rate_limit;
+pub mod retry;
 #[cfg(feature = "responses-streaming")]
 pub mod streaming;
+#[cfg(feature = "tower")]
+pub mod tower;
 
-static BASE_URL: LazyLock<Url> = LazyLock::new(|| "https://api.openai.com/".parse().unwrap());
+#[cfg(any(feature = "reqwest", feature = "tower"))]
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/";
+
+#[cfg(any(feature = "reqwest", feature = "tower"))]
+const ORGANIZATION_HEADER: &str = "OpenAI-Organization";
+#[cfg(any(feature = "reqwest", feature = "tower"))]
+const PROJECT_HEADER: &str = "OpenAI-Project";
+#[cfg(feature = "reqwest")]
+const API_KEY_HEADER: &str = "api-key";
+
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
 
 pub trait Transport {
     fn send<P, R>(
         &self,
         method: Method,
         path: &str,
-        params: Option<&P>,
-    ) -> impl Future<Output = Result<R, OpenAIError>> + Send
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
+    ) -> impl Future<Output = Result<WithMeta<R>, OpenAIError>> + Send
     where
         P: Sync + Serialize,
         R: DeserializeOwned;
 }
 
+/// Body of a request sent through a [`Transport`].
+pub enum RequestBody<'a, P = ()> {
+    /// No body.
+    None,
+    /// Params serialized as a JSON body.
+    Json(&'a P),
+    /// Params serialized into the query string.
+    Query(&'a P),
+    /// A `multipart/form-data` body.
+    Multipart(&'a MultipartForm),
+    /// Raw bytes with the given content type.
+    Raw { content_type: &'a str, bytes: Bytes },
+}
+
+impl RequestBody<'static, ()> {
+    pub fn none() -> Self {
+        Self::None
+    }
+}
+
+impl<P> RequestBody<'_, P>
+where
+    P: Serialize,
+{
+    /// Returns the JSON body with `extra_body` merged in. A missing body is replaced by `extra_body` if that is non-empty. Helps custom [`Transport`] implementations honour [`RequestOptions::extra_body`].
+    pub fn json(&self, extra_body: &Map<String, Value>) -> Result<Option<Value>, OpenAIError> {
+        match self {
+            Self::None if extra_body.is_empty() => Ok(None),
+            Self::None => Ok(Some(Value::Object(extra_body.clone()))),
+            Self::Json(params) => merge_body(*params, extra_body).map(Some),
+            _ => self.check_extra_body(extra_body).map(|()| None),
+        }
+    }
+
+    /// Fails if `extra_body` is non-empty but can't be merged into this body because it isn't JSON.
+    pub(crate) fn check_extra_body(
+        &self,
+        extra_body: &Map<String, Value>,
+    ) -> Result<(), OpenAIError> {
+        match self {
+            Self::None | Self::Json(_) => Ok(()),
+            _ if extra_body.is_empty() => Ok(()),
+            _ => Err(OpenAIError::Config {
+                name: "extra_body",
+                message: "only JSON request bodies can be extended".to_string(),
+            }),
+        }
+    }
+
+    /// Returns the query parameters of a [`RequestBody::Query`].
+    pub fn query(&self) -> Result<Vec<(String, String)>, OpenAIError> {
+        let Self::Query(params) = self else {
+            return Ok(vec![]);
+        };
+
+        let query = serde_urlencoded::to_string(params).context(SerializeQuerySnafu)?;
+
+        Ok(url::form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect())
+    }
+}
+
+/// Per-request overrides of the transport configuration.
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Retry policy used instead of the transport default.
+    pub retry: Option<RetryPolicy>,
+    /// Organization used instead of the transport default, sent as the `OpenAI-Organization` header.
+    pub organization: Option<String>,
+    /// Project used instead of the transport default, sent as the `OpenAI-Project` header.
+    pub project: Option<String>,
+    /// Headers added to the request, replacing default headers with the same name.
+    pub extra_headers: HeaderMap,
+    /// Query parameters appended to the request URL.
+    pub extra_query: Vec<(String, String)>,
+    /// Total timeout used instead of the transport default, covering the whole request including reading the response body. For streaming requests this bounds the duration of the whole stream.
+    ///
+    /// There is no per-request connect timeout, as connections are pooled and shared between requests; see [`StandardHttpTransportBuilder::connect_timeout`].
+    pub timeout: Option<Duration>,
+    /// Longest pause between two server-sent events used instead of the transport default. Exceeding it ends the stream with [`OpenAIStreamingError::IdleTimeout`](streaming::OpenAIStreamingError::IdleTimeout).
+    pub stream_idle_timeout: Option<Duration>,
+    /// Fields merged into the top level of the JSON request body, replacing fields with the same name. Useful for parameters not yet covered by the typed params. Requests with a query, multipart or raw body fail with [`OpenAIError::Config`] if this is non-empty.
+    pub extra_body: Map<String, Value>,
+}
+
+/// How the access token is sent.
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) enum AuthScheme {
+    /// `Authorization: Bearer <token>`
+    #[default]
+    Bearer,
+    /// The raw token in the `api-key` header, as used by Azure OpenAI.
+    ApiKey,
+}
+
+#[cfg(feature = "reqwest")]
+impl AuthScheme {
+    fn authenticate(
+        self,
+        builder: RequestBuilder,
+        access_token: &SecretString,
+    ) -> Result<RequestBuilder, OpenAIError> {
+        Ok(match self {
+            Self::Bearer => builder.bearer_auth(access_token.expose_secret()),
+            Self::ApiKey => {
+                let mut api_key =
+                    HeaderValue::from_str(access_token.expose_secret()).map_err(|err| {
+                        OpenAIError::Transport {
+                            source: err.into(),
+                            meta: None,
+                        }
+                    })?;
+                api_key.set_sensitive(true);
+                builder.header(API_KEY_HEADER, api_key)
+            }
+        })
+    }
+}
+
+#[cfg(feature = "reqwest")]
 #[derive(Clone)]
 pub struct StandardHttpTransport {
-    access_token: SecretString,
+    credentials: Arc<dyn CredentialProvider>,
+    auth_scheme: AuthScheme,
     client: reqwest::Client,
+    base_url: Url,
+    retry: RetryPolicy,
+    organization: Option<String>,
+    project: Option<String>,
+    default_headers: HeaderMap,
+    timeout: Option<Duration>,
+    #[cfg(feature = "responses-streaming")]
+    stream_idle_timeout: Option<Duration>,
+    #[cfg(feature = "tracing")]
+    trace_bodies: bool,
 }
 
+#[cfg(feature = "reqwest")]
 impl StandardHttpTransport {
     pub fn new(access_token: SecretString, client: reqwest::Client) -> Self {
-        Self {
-            access_token,
-            client,
-        }
+        Self::builder(access_token).with_client(client)
+    }
+
+    pub fn builder(access_token: SecretString) -> StandardHttpTransportBuilder {
+        StandardHttpTransportBuilder::new(access_token)
+    }
+
+    /// Starts building a transport that asks `credentials` for the access token of every request.
+    pub fn builder_with_credentials(
+        credentials: impl CredentialProvider + 'static,
+    ) -> StandardHttpTransportBuilder {
+        StandardHttpTransportBuilder::with_credentials(credentials)
+    }
+
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    /// Resolves an endpoint path such as `/v1/responses` against the base URL, keeping any path prefix of the base URL.
+    fn endpoint(&self, path: &str) -> Result<Url, OpenAIError> {
+        Ok(self.base_url.join(path.trim_start_matches('/'))?)
     }
 
     fn prepare_request<P>(
         &self,
         method: Method,
         path: &str,
-        params: Option<&P>,
+        body: &RequestBody<'_, P>,
+        options: &RequestOptions,
+        access_token: &SecretString,
     ) -> Result<RequestBuilder, OpenAIError>
     where
         P: Sync + Serialize,
     {
-        let mut builder = self
-            .client
-            .request(method.clone(), BASE_URL.join(path)?)
-            .bearer_auth(self.access_token.expose_secret());
-
-        if let Some(params) = params {
-            if method == Method::GET {
-                builder = builder.query(params);
-            } else if method == Method::POST {
-                builder = builder.json(params);
-            } else {
-                unimplemented!("Method {method} not supported");
-            }
+        let mut builder = self.client.request(method, self.endpoint(path)?);
+        builder = self.auth_scheme.authenticate(builder, access_token)?;
+        builder = builder.headers(self.default_headers.clone());
+
+        if let Some(organization) = options.organization.as_ref().or(self.organization.as_ref()) {
+            builder = builder.header(ORGANIZATION_HEADER, organization);
+        }
+
+        if let Some(project) = options.project.as_ref().or(self.project.as_ref()) {
+            builder = builder.header(PROJECT_HEADER, project);
+        }
+
+        builder = builder.headers(options.extra_headers.clone());
+
+        if let Some(timeout) = options.timeout.or(self.timeout) {
+            builder = builder.timeout(timeout);
+        }
+
+        body.check_extra_body(&options.extra_body)?;
+
+        builder = match body {
+            RequestBody::None if options.extra_body.is_empty() => builder,
+            RequestBody::None => builder.json(&options.extra_body),
+            RequestBody::Json(params) if options.extra_body.is_empty() => builder.json(params),
+            RequestBody::Json(params) => builder.json(&merge_body(*params, &options.extra_body)?),
+            RequestBody::Query(params) => builder.query(params),
+            RequestBody::Multipart(form) => builder.multipart(form.to_reqwest()?),
+            RequestBody::Raw {
+                content_type,
+                bytes,
+            } => builder
+                .header(header::CONTENT_TYPE, *content_type)
+                .body(bytes.clone()),
+        };
+
+        if !options.extra_query.is_empty() {
+            builder = builder.query(&options.extra_query);
         }
 
         Ok(builder)
     }
+
+    /// Sends the request, retrying according to the effective retry policy. Responses with a non-success status are turned into [`OpenAIError::Api`].
+    async fn execute<P>(
+        &self,
+        method: Method,
+        path: &str,
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
+        prepare: impl Fn(RequestBuilder) -> RequestBuilder,
+    ) -> Result<Response, OpenAIError>
+    where
+        P: Sync + Serialize,
+    {
+        let policy = options.retry.as_ref().unwrap_or(&self.retry);
+        let mut attempt = 1;
+
+        loop {
+            #[cfg(feature = "tracing")]
+            telemetry::record_attempt(attempt);
+
+            let access_token = self.credentials.credential().await?;
+            let builder = prepare(self.prepare_request(
+                method.clone(),
+                path,
+                &body,
+                options,
+                &access_token,
+            )?);
+
+            let err = match builder.send().await {
+                Ok(response) if response.status().is_success() => {
+                    #[cfg(feature = "tracing")]
+                    telemetry::record_response(response.status(), response.headers());
+
+                    return Ok(response);
+                }
+                Ok(response) => {
+                    let meta = ResponseMeta::new(response.status(), response.headers().clone());
+                    OpenAIError::api(response_text(response, &meta).await?, meta)
+                }
+                Err(err) => err.into(),
+            };
+
+            if attempt >= policy.max_attempts || !policy.retries(&err) {
+                #[cfg(feature = "tracing")]
+                telemetry::record_error(&tracing::Span::current(), &err);
+
+                return Err(err);
+            }
+
+            let headers = err.meta().map(|meta| &meta.headers);
+            tokio::time::sleep(policy.delay(attempt, headers)).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+pub struct StandardHttpTransportBuilder {
+    credentials: Arc<dyn CredentialProvider>,
+    auth_scheme: AuthScheme,
+    client: Option<reqwest::Client>,
+    base_url: Url,
+    retry: RetryPolicy,
+    organization: Option<String>,
+    project: Option<String>,
+    default_headers: HeaderMap,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    stream_idle_timeout: Option<Duration>,
+    #[cfg(feature = "__tls")]
+    root_certificates: Vec<Certificate>,
+    #[cfg(feature = "__tls")]
+    identity: Option<Identity>,
+    proxies: Vec<Proxy>,
+    no_proxy: bool,
+    #[cfg(feature = "tracing")]
+    trace_bodies: bool,
+}
+
+#[cfg(feature = "reqwest")]
+impl StandardHttpTransportBuilder {
+    pub fn new(access_token: SecretString) -> Self {
+        Self::with_credentials(access_token)
+    }
+
+    /// Creates a builder for a transport that asks `credentials` for the access token of every request, e.g. to support rotating keys.
+    pub fn with_credentials(credentials: impl CredentialProvider + 'static) -> Self {
+        Self {
+            credentials: Arc::new(credentials),
+            auth_scheme: AuthScheme::default(),
+            client: None,
+            base_url: DEFAULT_BASE_URL.parse().unwrap(),
+            retry: RetryPolicy::default(),
+            organization: None,
+            project: None,
+            default_headers: HeaderMap::new(),
+            connect_timeout: None,
+            timeout: None,
+            stream_idle_timeout: None,
+            #[cfg(feature = "__tls")]
+            root_certificates: Vec::new(),
+            #[cfg(feature = "__tls")]
+            identity: None,
+            proxies: Vec::new(),
+            no_proxy: false,
+            #[cfg(feature = "tracing")]
+            trace_bodies: false,
+        }
+    }
+
+    /// Reads the settings from the environment, see [`ClientConfig::from_env`].
+    pub fn from_env() -> Result<Self, OpenAIError> {
+        ClientConfig::from_env()?.builder()
+    }
+
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Sets the URL endpoint paths are resolved against. Default: `https://api.openai.com/`
+    ///
+    /// The base URL may carry a path prefix (e.g. `https://gateway.internal/openai/`), in which case `/v1/responses` resolves to `https://gateway.internal/openai/v1/responses`. A missing trailing slash is added.
+    pub fn base_url(mut self, base_url: Url) -> Self {
+        self.base_url = with_trailing_slash(base_url);
+        self
+    }
+
+    pub(crate) fn auth_scheme(mut self, auth_scheme: AuthScheme) -> Self {
+        self.auth_scheme = auth_scheme;
+        self
+    }
+
+    /// Sets the retry policy used for requests that don't override it. Default: [`RetryPolicy::default`]
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets the organization requests are attributed to, sent as the `OpenAI-Organization` header.
+    pub fn organization(mut self, organization: impl Into<String>) -> Self {
+        self.organization = Some(organization.into());
+        self
+    }
+
+    /// Sets the project requests are attributed to, sent as the `OpenAI-Project` header.
+    pub fn project(mut self, project: impl Into<String>) -> Self {
+        self.project = Some(project.into());
+        self
+    }
+
+    /// Sets headers sent with every request.
+    pub fn default_headers(mut self, default_headers: HeaderMap) -> Self {
+        self.default_headers = default_headers;
+        self
+    }
+
+    /// Sets the timeout for establishing connections. Only applies if no [`client`](Self::client) is given, configure the client directly otherwise.
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Sets the total timeout of requests that don't override it. See [`RequestOptions::timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the longest pause between server-sent events for requests that don't override it. See [`RequestOptions::stream_idle_timeout`].
+    pub fn stream_idle_timeout(mut self, stream_idle_timeout: Duration) -> Self {
+        self.stream_idle_timeout = Some(stream_idle_timeout);
+        self
+    }
+
+    /// Trusts `certificate` in addition to the root certificates of the TLS backend, e.g. the CA of a corporate gateway. Only applies if no [`client`](Self::client) is given.
+    #[cfg(feature = "__tls")]
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Presents `identity` as client certificate during the TLS handshake, for gateways requiring mutual TLS. Only applies if no [`client`](Self::client) is given.
+    #[cfg(feature = "__tls")]
+    pub fn identity(mut self, identity: Identity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Sends requests through `proxy`, e.g. `Proxy::https("http://proxy.internal:3128")?` or, with the `socks` feature, `Proxy::all("socks5://proxy.internal:1080")?`. Can be called multiple times, the first proxy matching a request is used. Only applies if no [`client`](Self::client) is given.
+    ///
+    /// Without explicit proxies, the proxies from the `HTTPS_PROXY`, `HTTP_PROXY` and `ALL_PROXY` environment variables (and, with the `system-proxy` feature, the macOS and Windows settings) are used unless [`no_proxy`](Self::no_proxy) is called.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Ignores the proxies from the environment, so requests are sent directly unless a [`proxy`](Self::proxy) is set. Only applies if no [`client`](Self::client) is given.
+    pub fn no_proxy(mut self) -> Self {
+        self.no_proxy = true;
+        self
+    }
+
+    /// Records request and response bodies in the `http.request.body` and `http.response.body` span fields. Bodies may contain sensitive data, so this is off by default.
+    #[cfg(feature = "tracing")]
+    pub fn trace_bodies(mut self, trace_bodies: bool) -> Self {
+        self.trace_bodies = trace_bodies;
+        self
+    }
+
+    /// Builds the transport, failing if the HTTP client can't be built, e.g. because a root certificate or identity is not supported by the TLS backend.
+    pub fn build(mut self) -> Result<StandardHttpTransport, OpenAIError> {
+        let client = match self.client.take() {
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                #[cfg(feature = "__tls")]
+                {
+                    for certificate in mem::take(&mut self.root_certificates) {
+                        builder = builder.add_root_certificate(certificate);
+                    }
+                    if let Some(identity) = self.identity.take() {
+                        builder = builder.identity(identity);
+                    }
+                }
+                if self.no_proxy {
+                    builder = builder.no_proxy();
+                }
+                for proxy in mem::take(&mut self.proxies) {
+                    builder = builder.proxy(proxy);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(self.with_client(client))
+    }
+
+    fn with_client(self, client: reqwest::Client) -> StandardHttpTransport {
+        StandardHttpTransport {
+            credentials: self.credentials,
+            auth_scheme: self.auth_scheme,
+            client,
+            base_url: self.base_url,
+            retry: self.retry,
+            organization: self.organization,
+            project: self.project,
+            default_headers: self.default_headers,
+            timeout: self.timeout,
+            #[cfg(feature = "responses-streaming")]
+            stream_idle_timeout: self.stream_idle_timeout,
+            #[cfg(feature = "tracing")]
+            trace_bodies: self.trace_bodies,
+        }
+    }
 }
 
+/// Reads the body of a response, attaching its metadata to errors.
+#[cfg(feature = "reqwest")]
+pub(crate) async fn response_text(
+    response: Response,
+    meta: &ResponseMeta,
+) -> Result<String, OpenAIError> {
+    response
+        .text()
+        .await
+        .map_err(|err| OpenAIError::from(err).with_meta(meta))
+}
+
+/// Appends a slash to the path of a base URL, so joining endpoint paths keeps its last segment.
+#[cfg(any(feature = "reqwest", feature = "tower"))]
+fn with_trailing_slash(mut base_url: Url) -> Url {
+    if !base_url.path().ends_with('/') {
+        let path = format!("{}/", base_url.path());
+        base_url.set_path(&path);
+    }
+    base_url
+}
+
+/// Serializes the params into a JSON object and merges the extra fields into it.
+fn merge_body<P>(params: &P, extra_body: &Map<String, Value>) -> Result<Value, OpenAIError>
+where
+    P: Serialize,
+{
+    let mut body = serde_json::to_value(params).context(SerializeRequestSnafu)?;
+
+    if let Value::Object(body) = &mut body {
+        body.extend(extra_body.clone());
+    }
+
+    Ok(body)
+}
+
+/// Deserializes a successful response body. An empty body, as sent with `204 No Content`, is read as `null`, so it deserializes into `()` or `None`.
+#[cfg_attr(
+    not(any(feature = "reqwest", feature = "tower", feature = "mock")),
+    allow(dead_code)
+)]
+pub(crate) fn deserialize_response<R>(
+    text: String,
+    meta: ResponseMeta,
+) -> Result<WithMeta<R>, OpenAIError>
+where
+    R: DeserializeOwned,
+{
+    let json = if text.trim().is_empty() {
+        "null"
+    } else {
+        &text
+    };
+
+    match serde_json::from_str(json) {
+        Ok(data) => Ok(WithMeta { data, meta }),
+        Err(source) => Err(source).context(DeserializeResponseSnafu {
+            text,
+            meta: Box::new(meta),
+        }),
+    }
+}
+
+#[cfg(feature = "reqwest")]
 impl Transport for StandardHttpTransport {
     async fn send<P, R>(
         &self,
         method: Method,
         path: &str,
-        params: Option<&P>,
-    ) -> Result<R, OpenAIError>
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
+    ) -> Result<WithMeta<R>, OpenAIError>
     where
         P: Sync + Serialize,
         R: DeserializeOwned,
     {
-        let builder = self.prepare_request(method.clone(), path, params)?;
+        #[cfg(feature = "tracing")]
+        let span = telemetry::http_span(
+            &method,
+            &self.endpoint(path)?,
+            &body,
+            options,
+            self.trace_bodies,
+        );
+
+        let response = async {
+            let response = self
+                .execute(method, path, body, options, |builder| builder)
+                .await?;
+
+            let meta = ResponseMeta::new(response.status(), response.headers().clone());
+            let text = response_text(response, &meta).await?;
+
+            #[cfg(feature = "tracing")]
+            telemetry::record_response_body(&text, self.trace_bodies);
+
+            deserialize_response(text, meta)
+        };
+
+        #[cfg(feature = "tracing")]
+        let response = tracing::Instrument::instrument(response, span);
+
+        response.await
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    #[cfg(any(feature = "reqwest", feature = "hyper"))]
+    use std::net::SocketAddr;
 
-        let response = builder.send().await?;
+    #[cfg(feature = "reqwest")]
+    use bytes::Bytes;
+    use http::StatusCode;
+    #[cfg(feature = "reqwest")]
+    use http::{Method, header};
+    #[cfg(feature = "reqwest")]
+    use serde_json::Value;
+    use serde_json::json;
+    #[cfg(any(feature = "reqwest", feature = "hyper"))]
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    };
 
-        let status = response.status();
-        let text = response.text().await?;
+    #[cfg(feature = "__tls")]
+    use crate::transport::Certificate;
+    #[cfg(feature = "reqwest")]
+    use crate::transport::{
+        MultipartForm, Proxy, RequestBody, RequestOptions, StandardHttpTransport, Transport,
+    };
+    use crate::{
+        OpenAIError,
+        models::DeletionStatus,
+        transport::{deserialize_response, merge_body, meta::ResponseMeta},
+    };
 
-        if status.is_success() {
-            serde_json::from_str(&text).context(DeserializeResponseSnafu { text })
-        } else {
-            Err(OpenAIError::Api { status, text })
+    /// Serves the raw HTTP responses on a loopback port, one connection each, and returns the port's address and the requests received.
+    #[cfg(any(feature = "reqwest", feature = "hyper"))]
+    pub(crate) async fn serve(responses: Vec<String>) -> (SocketAddr, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                let mut len = None;
+                while len.is_none_or(|len| request.len() < len) {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    assert_ne!(n, 0);
+                    request.extend_from_slice(&buf[..n]);
+
+                    if len.is_none()
+                        && let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n")
+                    {
+                        len = Some(end + 4 + content_length(&request[..end]));
+                    }
+                }
+
+                socket.write_all(response.as_bytes()).await.unwrap();
+                requests.push(String::from_utf8(request).unwrap());
+            }
+
+            requests
+        });
+
+        (address, server)
+    }
+
+    #[cfg(any(feature = "reqwest", feature = "hyper"))]
+    fn content_length(head: &[u8]) -> usize {
+        String::from_utf8_lossy(head)
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .map_or(0, |(_, value)| value.trim().parse().unwrap())
+    }
+
+    /// A raw HTTP response closing the connection after the body.
+    #[cfg(any(
+        feature = "reqwest",
+        all(feature = "hyper", feature = "responses-streaming")
+    ))]
+    pub(crate) fn http_response(status: StatusCode, content_type: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {status}\r\ncontent-type: {content_type}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    #[cfg(feature = "reqwest")]
+    fn endpoint(base_url: &str, path: &str) -> String {
+        StandardHttpTransport::builder("sk-test".into())
+            .base_url(base_url.parse().unwrap())
+            .build()
+            .unwrap()
+            .endpoint(path)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    #[cfg(feature = "reqwest")]
+    fn test_endpoint() {
+        assert_eq!(
+            endpoint("https://api.openai.com", "/v1/responses"),
+            "https://api.openai.com/v1/responses"
+        );
+        assert_eq!(
+            endpoint("https://gateway.internal/openai/", "/v1/responses"),
+            "https://gateway.internal/openai/v1/responses"
+        );
+        assert_eq!(
+            endpoint("https://gateway.internal/openai", "/v1/responses/resp_123"),
+            "https://gateway.internal/openai/v1/responses/resp_123"
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "reqwest")]
+    async fn test_proxy() {
+        let (address, server) = serve(vec![http_response(
+            StatusCode::OK,
+            "application/json",
+            "{}",
+        )])
+        .await;
+        let proxy = format!("http://{address}");
+
+        let transport = StandardHttpTransport::builder("sk-test".into())
+            .base_url("http://api.openai.test/".parse().unwrap())
+            .proxy(Proxy::http(proxy).unwrap())
+            .build()
+            .unwrap();
+
+        Transport::send::<_, Value>(
+            &transport,
+            Method::GET,
+            "/v1/models",
+            RequestBody::none(),
+            &RequestOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        // Proxies receive the absolute URL in the request line.
+        assert!(
+            server.await.unwrap()[0]
+                .starts_with("GET http://api.openai.test/v1/models HTTP/1.1\r\n")
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "reqwest")]
+    async fn test_body_error_keeps_meta() {
+        // The connection is closed before the announced body is complete.
+        let (address, _server) = serve(vec![
+            "HTTP/1.1 200 OK\r\nx-request-id: req_123\r\ncontent-length: 100\r\n\r\n{}".to_string(),
+        ])
+        .await;
+
+        let transport = StandardHttpTransport::builder("sk-test".into())
+            .base_url(format!("http://{address}").parse().unwrap())
+            .build()
+            .unwrap();
+
+        let err = Transport::send::<_, Value>(
+            &transport,
+            Method::GET,
+            "/v1/models",
+            RequestBody::none(),
+            &RequestOptions::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, OpenAIError::Reqwest { .. }));
+        assert_eq!(err.request_id(), Some("req_123"));
+        assert_eq!(err.meta().unwrap().status, StatusCode::OK);
+    }
+
+    #[test]
+    #[cfg(feature = "__tls")]
+    fn test_invalid_root_certificate() {
+        let Ok(certificate) = Certificate::from_der(b"not a certificate") else {
+            // Some TLS backends already reject the certificate here.
+            return;
+        };
+
+        let result = StandardHttpTransport::builder("sk-test".into())
+            .add_root_certificate(certificate)
+            .build();
+
+        assert!(matches!(result, Err(OpenAIError::Reqwest { .. })));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "reqwest")]
+    async fn test_prepare_request() {
+        let (address, server) = serve(vec![http_response(
+            StatusCode::OK,
+            "application/json",
+            "{}",
+        )])
+        .await;
+        let transport = StandardHttpTransport::builder("sk-test".into())
+            .base_url(format!("http://{address}").parse().unwrap())
+            .build()
+            .unwrap();
+        let access_token = "sk-test".into();
+        let options = RequestOptions::default();
+        let prepare = |method, body: &RequestBody<'_, Value>, options| {
+            transport
+                .prepare_request(method, "/v1/files", body, options, &access_token)
+                .unwrap()
+                .build()
+                .unwrap()
+        };
+        let content_type = |request: &reqwest::Request| {
+            request.headers()[header::CONTENT_TYPE]
+                .to_str()
+                .unwrap()
+                .to_string()
+        };
+
+        let params = json!({"id": "file_123"});
+        let request = prepare(Method::DELETE, &RequestBody::Json(&params), &options);
+        assert_eq!(request.method(), Method::DELETE);
+        assert_eq!(content_type(&request), "application/json");
+        assert_eq!(
+            request.body().and_then(reqwest::Body::as_bytes),
+            Some(br#"{"id":"file_123"}"#.as_slice())
+        );
+
+        let request = prepare(
+            Method::POST,
+            &RequestBody::Raw {
+                content_type: "audio/wav",
+                bytes: Bytes::from_static(b"RIFF"),
+            },
+            &options,
+        );
+        assert_eq!(request.method(), Method::POST);
+        assert_eq!(content_type(&request), "audio/wav");
+        assert_eq!(
+            request.body().and_then(reqwest::Body::as_bytes),
+            Some(b"RIFF".as_slice())
+        );
+
+        let form = MultipartForm::new().file(
+            "file",
+            "input.jsonl",
+            Some("application/jsonl".to_string()),
+            "{}",
+        );
+        let request = prepare(Method::POST, &RequestBody::Multipart(&form), &options);
+        assert_eq!(request.method(), Method::POST);
+        assert!(content_type(&request).starts_with("multipart/form-data; boundary="));
+
+        transport.client.execute(request).await.unwrap();
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("POST /v1/files HTTP/1.1\r\n"));
+        assert!(requests[0].contains(
+            "Content-Disposition: form-data; name=\"file\"; filename=\"input.jsonl\"\r\nContent-Type: application/jsonl\r\n\r\n{}\r\n"
+        ));
+
+        let options = RequestOptions {
+            extra_body: json!({"purpose": "batch"}).as_object().unwrap().clone(),
+            ..Default::default()
+        };
+        let bodies: [RequestBody<'_, Value>; 3] = [
+            RequestBody::Query(&params),
+            RequestBody::Multipart(&form),
+            RequestBody::Raw {
+                content_type: "audio/wav",
+                bytes: Bytes::from_static(b"RIFF"),
+            },
+        ];
+        for body in &bodies {
+            assert!(matches!(
+                transport.prepare_request(Method::POST, "/v1/files", body, &options, &access_token),
+                Err(OpenAIError::Config {
+                    name: "extra_body",
+                    ..
+                })
+            ));
         }
     }
+
+    #[test]
+    fn test_merge_body() {
+        let extra_body = json!({"service_tier": "flex", "input": "replaced"});
+        let extra_body = extra_body.as_object().unwrap();
+
+        assert_eq!(
+            merge_body(&json!({"input": "Hello", "background": true}), extra_body).unwrap(),
+            json!({"input": "replaced", "background": true, "service_tier": "flex"})
+        );
+    }
+
+    #[test]
+    fn test_deserialize_response() {
+        let meta = || ResponseMeta::new(StatusCode::OK, Default::default());
+
+        let deleted: DeletionStatus = deserialize_response(
+            r#"{"id": "resp_123", "object": "response", "deleted": true}"#.to_string(),
+            meta(),
+        )
+        .unwrap()
+        .into_inner();
+        assert_eq!(
+            deleted,
+            DeletionStatus {
+                id: "resp_123".to_string(),
+                object: "response".to_string(),
+                deleted: true,
+            }
+        );
+
+        let meta = ResponseMeta::new(StatusCode::NO_CONTENT, Default::default());
+        deserialize_response::<()>(String::new(), meta).unwrap();
+
+        assert!(matches!(
+            deserialize_response::<DeletionStatus>(String::new(), ResponseMeta::default()),
+            Err(OpenAIError::DeserializeResponse { .. })
+        ));
+    }
 }
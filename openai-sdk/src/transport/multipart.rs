@@ -0,0 +1,190 @@
+use bytes::Bytes;
+use http::HeaderValue;
+
+use crate::OpenAIError;
+
+/// A `multipart/form-data` request body, e.g. for file uploads.
+///
+/// Parts are kept in memory so the form can be sent again when a request is retried.
+#[derive(Debug, Clone, Default)]
+pub struct MultipartForm {
+    parts: Vec<MultipartPart>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MultipartPart {
+    pub name: String,
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub data: Bytes,
+}
+
+impl MultipartForm {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a text field.
+    pub fn text(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.parts.push(MultipartPart {
+            name: name.into(),
+            filename: None,
+            content_type: None,
+            data: Bytes::from(value.into()),
+        });
+        self
+    }
+
+    /// Adds a file field.
+    pub fn file(
+        mut self,
+        name: impl Into<String>,
+        filename: impl Into<String>,
+        content_type: Option<String>,
+        data: impl Into<Bytes>,
+    ) -> Self {
+        self.parts.push(MultipartPart {
+            name: name.into(),
+            filename: Some(filename.into()),
+            content_type,
+            data: data.into(),
+        });
+        self
+    }
+
+    pub fn part(mut self, part: MultipartPart) -> Self {
+        self.parts.push(part);
+        self
+    }
+
+    pub fn parts(&self) -> &[MultipartPart] {
+        &self.parts
+    }
+
+    /// Encodes the form for transports that don't build on reqwest, returning the `Content-Type` header value and the body. Fails with [`OpenAIError::Config`] if the content type of a part is not a valid header value.
+    pub fn encode(&self) -> Result<(String, Bytes), OpenAIError> {
+        let boundary = format!("----openai-sdk-{:016x}", fastrand::u64(..));
+        let mut body = Vec::new();
+
+        for part in &self.parts {
+            body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+            body.extend_from_slice(
+                format!(
+                    "Content-Disposition: form-data; name=\"{}\"",
+                    escape_quoted(&part.name)
+                )
+                .as_bytes(),
+            );
+            if let Some(filename) = &part.filename {
+                body.extend_from_slice(
+                    format!("; filename=\"{}\"", escape_quoted(filename)).as_bytes(),
+                );
+            }
+            body.extend_from_slice(b"\r\n");
+            if let Some(content_type) = &part.content_type {
+                if HeaderValue::from_str(content_type).is_err() {
+                    return Err(OpenAIError::Config {
+                        name: "content_type",
+                        message: format!("invalid content type of part {}", part.name),
+                    });
+                }
+                body.extend_from_slice(format!("Content-Type: {content_type}\r\n").as_bytes());
+            }
+            body.extend_from_slice(b"\r\n");
+            body.extend_from_slice(&part.data);
+            body.extend_from_slice(b"\r\n");
+        }
+
+        body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+
+        Ok((
+            format!("multipart/form-data; boundary={boundary}"),
+            body.into(),
+        ))
+    }
+
+    #[cfg(feature = "reqwest")]
+    pub(crate) fn to_reqwest(&self) -> Result<reqwest::multipart::Form, reqwest::Error> {
+        let mut form = reqwest::multipart::Form::new();
+
+        for part in &self.parts {
+            let len = part.data.len() as u64;
+            let mut reqwest_part = reqwest::multipart::Part::stream_with_length(
+                reqwest::Body::from(part.data.clone()),
+                len,
+            );
+
+            if let Some(filename) = &part.filename {
+                reqwest_part = reqwest_part.file_name(filename.clone());
+            }
+
+            if let Some(content_type) = &part.content_type {
+                reqwest_part = reqwest_part.mime_str(content_type)?;
+            }
+
+            form = form.part(part.name.clone(), reqwest_part);
+        }
+
+        Ok(form)
+    }
+}
+
+/// Escapes a `Content-Disposition` parameter value the way browsers do.
+fn escape_quoted(value: &str) -> String {
+    value
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{OpenAIError, transport::multipart::MultipartForm};
+
+    #[test]
+    fn test_encode() {
+        let form = MultipartForm::new().text("purpose", "batch").file(
+            "file",
+            "in\"put.jsonl",
+            Some("application/jsonl".to_string()),
+            "{}",
+        );
+
+        let (content_type, body) = form.encode().unwrap();
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(body.to_vec()).unwrap(),
+            format!(
+                "--{boundary}\r\n\
+                Content-Disposition: form-data; name=\"purpose\"\r\n\r\n\
+                batch\r\n\
+                --{boundary}\r\n\
+                Content-Disposition: form-data; name=\"file\"; filename=\"in%22put.jsonl\"\r\n\
+                Content-Type: application/jsonl\r\n\r\n\
+                {{}}\r\n\
+                --{boundary}--\r\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_encode_invalid_content_type() {
+        let form = MultipartForm::new().file(
+            "file",
+            "input.jsonl",
+            Some("text/plain\r\nX-Injected: 1".to_string()),
+            "{}",
+        );
+
+        assert!(matches!(
+            form.encode(),
+            Err(OpenAIError::Config {
+                name: "content_type",
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,497 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use http::Method;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use tokio::time::Instant;
+
+use crate::{
+    OpenAIError,
+    transport::{
+        RequestBody, RequestOptions, Transport,
+        meta::{RateLimitInfo, ResponseMeta, WithMeta},
+        retry::retry_after,
+    },
+};
+
+/// How long all requests are held back after a rate limited response without a delay hint.
+const DEFAULT_PAUSE: Duration = Duration::from_secs(1);
+
+/// Longest delay hint of a rate limited response that is honoured by default.
+const DEFAULT_MAX_PAUSE: Duration = Duration::from_secs(60);
+
+/// Longest single wait for a bucket to refill. [`RateLimiter::acquire`] checks the budget again afterwards, so slow refill rates learned from huge reset headers only delay requests, they don't overflow.
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// Client-side budget of requests and tokens per minute, delaying requests that would exceed it instead of letting them fail with `429 Too Many Requests`.
+///
+/// Limits can be configured up front and are learned from the `x-ratelimit-*` headers of every response, so a limiter without configuration starts throttling after the first response. Clones share their budget, so one limiter can be used by many clients and workers.
+#[derive(Clone)]
+pub struct RateLimiter {
+    state: Arc<Mutex<RateLimiterState>>,
+    estimate_tokens: fn(&Value) -> u64,
+    max_pause: Duration,
+}
+
+#[derive(Debug, Default)]
+struct RateLimiterState {
+    requests: Bucket,
+    tokens: Bucket,
+    paused_until: Option<Instant>,
+}
+
+/// A token bucket that refills continuously and is full after a minute.
+#[derive(Debug, Default)]
+struct Bucket {
+    limit: Option<u64>,
+    available: f64,
+    refill_per_sec: f64,
+    updated_at: Option<Instant>,
+}
+
+impl Bucket {
+    fn configure(&mut self, limit: u64, now: Instant) {
+        self.limit = Some(limit);
+        self.available = limit as f64;
+        self.refill_per_sec = limit as f64 / 60.0;
+        self.updated_at = Some(now);
+    }
+
+    fn refill(&mut self, now: Instant) {
+        if let (Some(limit), Some(updated_at)) = (self.limit, self.updated_at) {
+            let elapsed = now.saturating_duration_since(updated_at).as_secs_f64();
+            self.available = (self.available + elapsed * self.refill_per_sec).min(limit as f64);
+        }
+        self.updated_at = Some(now);
+    }
+
+    /// Adopts the state reported by the server.
+    fn learn(
+        &mut self,
+        limit: Option<u64>,
+        remaining: Option<u64>,
+        reset: Option<Duration>,
+        now: Instant,
+    ) {
+        let Some(limit) = limit.or(self.limit) else {
+            return;
+        };
+
+        self.limit = Some(limit);
+        self.refill_per_sec = limit as f64 / 60.0;
+
+        if let Some(remaining) = remaining {
+            self.available = remaining as f64;
+
+            // The reset header tells when the bucket is full again, which is more accurate than assuming a one minute window.
+            if let Some(reset) = reset.filter(|reset| !reset.is_zero())
+                && remaining < limit
+            {
+                self.refill_per_sec = (limit - remaining) as f64 / reset.as_secs_f64();
+            }
+        }
+
+        self.updated_at = Some(now);
+    }
+
+    /// Returns how long to wait until `cost` is available. Costs above the limit wait for a full bucket.
+    fn wait(&self, cost: u64) -> Duration {
+        let Some(limit) = self.limit else {
+            return Duration::ZERO;
+        };
+
+        let missing = cost.min(limit) as f64 - self.available;
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else if self.refill_per_sec <= 0.0 {
+            DEFAULT_PAUSE
+        } else {
+            Duration::try_from_secs_f64(missing / self.refill_per_sec)
+                .unwrap_or(MAX_WAIT)
+                .min(MAX_WAIT)
+        }
+    }
+
+    fn take(&mut self, cost: u64) {
+        if let Some(limit) = self.limit {
+            self.available -= cost.min(limit) as f64;
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self {
+            state: Default::default(),
+            estimate_tokens,
+            max_pause: DEFAULT_MAX_PAUSE,
+        }
+    }
+}
+
+impl RateLimiter {
+    /// Creates a limiter that learns the limits from response headers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of requests allowed per minute until a response reports the real limit.
+    pub fn requests_per_minute(self, limit: u64) -> Self {
+        self.state
+            .lock()
+            .unwrap()
+            .requests
+            .configure(limit, Instant::now());
+        self
+    }
+
+    /// Sets the number of tokens allowed per minute until a response reports the real limit.
+    pub fn tokens_per_minute(self, limit: u64) -> Self {
+        self.state
+            .lock()
+            .unwrap()
+            .tokens
+            .configure(limit, Instant::now());
+        self
+    }
+
+    /// Sets the function estimating the tokens a JSON request body consumes. Default: [`estimate_tokens`]
+    pub fn token_estimator(mut self, estimate_tokens: fn(&Value) -> u64) -> Self {
+        self.estimate_tokens = estimate_tokens;
+        self
+    }
+
+    /// Sets the longest pause after a rate limited response. Longer delays requested by the server are cut to this, so a server can't hold back all clients sharing the limiter indefinitely.
+    /// Default: 60s
+    pub fn max_pause(mut self, max_pause: Duration) -> Self {
+        self.max_pause = max_pause;
+        self
+    }
+
+    /// Waits until one request costing `tokens` fits into the budget, then takes it from the budget.
+    pub async fn acquire(&self, tokens: u64) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                let now = Instant::now();
+
+                match state.paused_until.filter(|until| *until > now) {
+                    Some(until) => until - now,
+                    None => {
+                        state.requests.refill(now);
+                        state.tokens.refill(now);
+
+                        let wait = state.requests.wait(1).max(state.tokens.wait(tokens));
+                        if wait.is_zero() {
+                            state.requests.take(1);
+                            state.tokens.take(tokens);
+                            return;
+                        }
+                        wait
+                    }
+                }
+            };
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Updates the budget from the headers of a response.
+    pub fn observe(&self, meta: &ResponseMeta) {
+        let RateLimitInfo {
+            limit_requests,
+            limit_tokens,
+            remaining_requests,
+            remaining_tokens,
+            reset_requests,
+            reset_tokens,
+        } = meta.rate_limit;
+
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+
+        state
+            .requests
+            .learn(limit_requests, remaining_requests, reset_requests, now);
+        state
+            .tokens
+            .learn(limit_tokens, remaining_tokens, reset_tokens, now);
+    }
+
+    /// Updates the budget from a failed request, holding back all requests for as long as the server asks, up to [`RateLimiter::max_pause`], if it was rate limited.
+    pub fn observe_error(&self, err: &OpenAIError) {
+        let Some(meta) = err.meta() else {
+            return;
+        };
+
+        self.observe(meta);
+
+        if err.is_rate_limited() {
+            let pause = retry_after(&meta.headers)
+                .unwrap_or(DEFAULT_PAUSE)
+                .min(self.max_pause);
+            let now = Instant::now();
+            self.state.lock().unwrap().paused_until = now
+                .checked_add(pause)
+                .or_else(|| now.checked_add(DEFAULT_MAX_PAUSE));
+        }
+    }
+
+    /// Estimates the token cost of a request, unless no token limit is known yet.
+    fn estimate<P>(&self, body: &RequestBody<'_, P>, options: &RequestOptions) -> u64
+    where
+        P: Serialize,
+    {
+        if self.state.lock().unwrap().tokens.limit.is_none() {
+            return 0;
+        }
+
+        match body.json(&options.extra_body) {
+            Ok(Some(json)) => (self.estimate_tokens)(&json),
+            _ => 0,
+        }
+    }
+}
+
+/// Estimates the tokens a request body consumes: about four characters per token of its `input` and `instructions`, plus the `max_output_tokens` it may generate, which count towards the limit as well.
+pub fn estimate_tokens(body: &Value) -> u64 {
+    fn chars(value: &Value) -> usize {
+        match value {
+            Value::String(text) => text.chars().count(),
+            Value::Array(values) => values.iter().map(chars).sum(),
+            Value::Object(fields) => fields.values().map(chars).sum(),
+            _ => 0,
+        }
+    }
+
+    let chars = chars(&body["input"]) + chars(&body["instructions"]);
+
+    chars.div_ceil(4) as u64 + body["max_output_tokens"].as_u64().unwrap_or(0)
+}
+
+/// A transport wrapper that waits for a [`RateLimiter`] before every request and feeds it the response headers.
+#[derive(Clone)]
+pub struct RateLimited<T> {
+    inner: T,
+    limiter: RateLimiter,
+}
+
+impl<T> RateLimited<T> {
+    pub fn new(inner: T, limiter: RateLimiter) -> Self {
+        Self { inner, limiter }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn limiter(&self) -> &RateLimiter {
+        &self.limiter
+    }
+}
+
+impl<T> Transport for RateLimited<T>
+where
+    T: Transport + Sync,
+{
+    async fn send<P, R>(
+        &self,
+        method: Method,
+        path: &str,
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
+    ) -> Result<WithMeta<R>, OpenAIError>
+    where
+        P: Sync + Serialize,
+        R: DeserializeOwned,
+    {
+        self.limiter
+            .acquire(self.limiter.estimate(&body, options))
+            .await;
+
+        let result = self.inner.send(method, path, body, options).await;
+
+        match &result {
+            Ok(response) => self.limiter.observe(&response.meta),
+            Err(err) => self.limiter.observe_error(err),
+        }
+
+        result
+    }
+}
+
+#[cfg(feature = "responses-streaming")]
+mod streaming {
+    use http::Method;
+    use serde::{Serialize, de::DeserializeOwned};
+
+    use crate::{
+        OpenAIError,
+        transport::{
+            RequestBody, RequestOptions,
+            rate_limit::RateLimited,
+            streaming::{ParsedEventStream, StreamingTransport},
+        },
+    };
+
+    impl<T> StreamingTransport for RateLimited<T>
+    where
+        T: StreamingTransport + Sync,
+    {
+        async fn send<P, E>(
+            &self,
+            method: Method,
+            path: &str,
+            body: RequestBody<'_, P>,
+            options: &RequestOptions,
+        ) -> Result<ParsedEventStream<E>, OpenAIError>
+        where
+            P: Sync + Serialize,
+            E: Send + DeserializeOwned,
+        {
+            self.limiter
+                .acquire(self.limiter.estimate(&body, options))
+                .await;
+
+            let result = self.inner.send(method, path, body, options).await;
+
+            match &result {
+                Ok(stream) => self.limiter.observe(stream.meta()),
+                Err(err) => self.limiter.observe_error(err),
+            }
+
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use http::{Method, StatusCode, header::HeaderName};
+    use serde_json::{Value, json};
+    use tokio::time::Instant;
+
+    use crate::transport::{
+        RequestBody, RequestOptions, Transport,
+        mock::MockTransport,
+        rate_limit::{Bucket, MAX_WAIT, RateLimited, RateLimiter, estimate_tokens},
+    };
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(
+            estimate_tokens(&json!({
+                "model": "gpt-4o",
+                "instructions": "Be brief",
+                "input": [{ "role": "user", "content": "Hello there" }],
+                "max_output_tokens": 100,
+            })),
+            // 8 + 4 + 11 characters
+            106
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_requests_per_minute() {
+        let limiter = RateLimiter::new().requests_per_minute(2);
+        let start = Instant::now();
+
+        limiter.acquire(0).await;
+        limiter.acquire(0).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        limiter.acquire(0).await;
+        assert!(start.elapsed() >= Duration::from_secs(30));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_learn_from_headers() {
+        let mock = MockTransport::new();
+        let mut expectation = mock.expect(Method::POST, "/v1/responses").times(2);
+        for (name, value) in [
+            ("x-ratelimit-limit-tokens", "1000"),
+            ("x-ratelimit-remaining-tokens", "0"),
+            ("x-ratelimit-reset-tokens", "10s"),
+        ] {
+            expectation = expectation.reply_header(HeaderName::from_static(name), value);
+        }
+        expectation.respond_json(StatusCode::OK, json!({}));
+
+        let transport = RateLimited::new(mock.clone(), RateLimiter::new());
+        let body = json!({ "input": "x".repeat(400) });
+        let start = Instant::now();
+
+        for _ in 0..2 {
+            Transport::send::<_, Value>(
+                &transport,
+                Method::POST,
+                "/v1/responses",
+                RequestBody::Json(&body),
+                &RequestOptions::default(),
+            )
+            .await
+            .unwrap();
+        }
+
+        // 100 tokens at 100 tokens per second
+        assert!(start.elapsed() >= Duration::from_secs(1));
+        assert!(start.elapsed() < Duration::from_secs(2));
+
+        mock.assert_done();
+    }
+
+    #[test]
+    fn test_wait_slow_refill() {
+        let bucket = Bucket {
+            limit: Some(1000),
+            available: 0.0,
+            refill_per_sec: 1e-30,
+            updated_at: None,
+        };
+
+        assert_eq!(bucket.wait(1000), MAX_WAIT);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_absurd_retry_after() {
+        let mock = MockTransport::new();
+        mock.expect(Method::POST, "/v1/responses")
+            .reply_header(
+                HeaderName::from_static("retry-after"),
+                u64::MAX.to_string(),
+            )
+            .respond_json(
+                StatusCode::TOO_MANY_REQUESTS,
+                json!({"error": {"message": "Slow down", "type": "requests", "param": null, "code": "rate_limit_exceeded"}}),
+            );
+        mock.expect(Method::POST, "/v1/responses")
+            .respond_json(StatusCode::OK, json!({}));
+
+        let transport = RateLimited::new(
+            mock.clone(),
+            RateLimiter::new().max_pause(Duration::from_secs(5)),
+        );
+        let options = RequestOptions::default();
+        let send = || {
+            Transport::send::<_, Value>(
+                &transport,
+                Method::POST,
+                "/v1/responses",
+                RequestBody::none(),
+                &options,
+            )
+        };
+        let start = Instant::now();
+
+        send().await.unwrap_err();
+        send().await.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+
+        mock.assert_done();
+    }
+}
@@ -0,0 +1,238 @@
+use std::time::Duration;
+
+use http::header::HeaderMap;
+
+use crate::OpenAIError;
+
+const RETRY_AFTER_MS: &str = "retry-after-ms";
+const RETRY_AFTER: &str = "retry-after";
+const RATELIMIT_RESET_REQUESTS: &str = "x-ratelimit-reset-requests";
+const RATELIMIT_RESET_TOKENS: &str = "x-ratelimit-reset-tokens";
+
+/// Controls how failed requests are retried.
+///
+/// Requests are retried on errors accepted by [`RetryPolicy::retry_on`]. Delays grow exponentially from `base_delay` up to `max_delay`, unless the server asks for a specific delay through `retry-after-ms`, `retry-after` or `x-ratelimit-reset-*` headers.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one. `1` disables retries.
+    /// Default: 3
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    /// Default: 500ms
+    pub base_delay: Duration,
+    /// Upper bound for computed backoff delays.
+    /// Default: 8s
+    pub max_delay: Duration,
+    /// Longest server-provided delay that is honoured. Longer hints fall back to the computed backoff.
+    /// Default: 60s
+    pub max_retry_after: Duration,
+    /// Whether to randomize computed backoff delays by up to 25% to avoid synchronized retries.
+    /// Default: true
+    pub jitter: bool,
+    /// Decides whether a failed attempt is retried.
+    /// Default: [`OpenAIError::is_retryable`]
+    pub retry_on: fn(&OpenAIError) -> bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+            max_retry_after: Duration::from_secs(60),
+            jitter: true,
+            retry_on: OpenAIError::is_retryable,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    #[cfg_attr(not(any(feature = "reqwest", feature = "tower")), allow(dead_code))]
+    pub(crate) fn retries(&self, err: &OpenAIError) -> bool {
+        (self.retry_on)(err)
+    }
+
+    /// Returns the delay before attempt number `attempt + 1`, given the headers of the failed response if any.
+    #[cfg_attr(not(any(feature = "reqwest", feature = "tower")), allow(dead_code))]
+    pub(crate) fn delay(&self, attempt: u32, headers: Option<&HeaderMap>) -> Duration {
+        if let Some(delay) = headers.and_then(retry_after)
+            && delay <= self.max_retry_after
+        {
+            return delay;
+        }
+
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+
+        if self.jitter {
+            delay.mul_f64(1.0 - fastrand::f64() * 0.25)
+        } else {
+            delay
+        }
+    }
+}
+
+/// Extracts the delay requested by the server, preferring `retry-after-ms` over `retry-after` over `x-ratelimit-reset-*`. Headers that can't be parsed are skipped.
+///
+/// Only the delay-seconds form of `retry-after` is supported; the HTTP-date form is ignored.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+    if let Some(delay) = header(RETRY_AFTER_MS)
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .and_then(|ms| Duration::try_from_secs_f64(ms / 1000.0).ok())
+    {
+        return Some(delay);
+    }
+
+    if let Some(secs) = header(RETRY_AFTER).and_then(|value| value.trim().parse::<u64>().ok()) {
+        return Some(Duration::from_secs(secs));
+    }
+
+    [RATELIMIT_RESET_REQUESTS, RATELIMIT_RESET_TOKENS]
+        .into_iter()
+        .filter_map(|name| header(name).and_then(parse_reset_duration))
+        .max()
+}
+
+/// Parses durations in the format used by `x-ratelimit-reset-*` headers, e.g. `20ms`, `1.5s` or `6m0s`.
+pub(crate) fn parse_reset_duration(value: &str) -> Option<Duration> {
+    let mut total = Duration::ZERO;
+    let mut rest = value.trim();
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let split = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .filter(|split| *split > 0)?;
+        let (number, tail) = rest.split_at(split);
+        let number: f64 = number.parse().ok()?;
+
+        let unit_len = tail
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+
+        let secs = match unit {
+            "ms" => number / 1000.0,
+            "s" => number,
+            "m" => number * 60.0,
+            "h" => number * 3600.0,
+            _ => return None,
+        };
+
+        total = total.checked_add(Duration::try_from_secs_f64(secs).ok()?)?;
+        rest = tail;
+    }
+
+    Some(total)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use http::header::{HeaderMap, HeaderName, HeaderValue};
+
+    use crate::transport::retry::{RetryPolicy, parse_reset_duration, retry_after};
+
+    #[test]
+    fn test_parse_reset_duration() {
+        assert_eq!(
+            parse_reset_duration("20ms"),
+            Some(Duration::from_millis(20))
+        );
+        assert_eq!(
+            parse_reset_duration("1.5s"),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(parse_reset_duration("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(
+            parse_reset_duration("1h2m3s"),
+            Some(Duration::from_secs(3723))
+        );
+        assert_eq!(parse_reset_duration(""), None);
+        assert_eq!(parse_reset_duration("soon"), None);
+        assert_eq!(
+            parse_reset_duration("10000000000000000000s10000000000000000000s"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_delay() {
+        let policy = RetryPolicy {
+            jitter: false,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.delay(1, None), Duration::from_millis(500));
+        assert_eq!(policy.delay(3, None), Duration::from_secs(2));
+        assert_eq!(policy.delay(10, None), Duration::from_secs(8));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-reset-requests", HeaderValue::from_static("1s"));
+        headers.insert("x-ratelimit-reset-tokens", HeaderValue::from_static("6s"));
+        assert_eq!(policy.delay(1, Some(&headers)), Duration::from_secs(6));
+
+        headers.insert("retry-after", HeaderValue::from_static("2"));
+        assert_eq!(policy.delay(1, Some(&headers)), Duration::from_secs(2));
+
+        headers.insert("retry-after-ms", HeaderValue::from_static("150"));
+        assert_eq!(policy.delay(1, Some(&headers)), Duration::from_millis(150));
+
+        headers.insert("retry-after-ms", HeaderValue::from_static("3600000"));
+        assert_eq!(policy.delay(1, Some(&headers)), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let headers = |pairs: &[(&'static str, &'static str)]| {
+            pairs
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        HeaderName::from_static(name),
+                        HeaderValue::from_static(value),
+                    )
+                })
+                .collect::<HeaderMap>()
+        };
+
+        assert_eq!(
+            retry_after(&headers(&[("retry-after-ms", "-5"), ("retry-after", "2")])),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            retry_after(&headers(&[
+                ("retry-after-ms", "soon"),
+                ("x-ratelimit-reset-requests", "1s")
+            ])),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(retry_after(&headers(&[("retry-after", "1e19")])), None);
+        assert_eq!(retry_after(&headers(&[("retry-after", "inf")])), None);
+        assert_eq!(
+            retry_after(&headers(&[(
+                "retry-after",
+                "Wed, 21 Oct 2015 07:28:00 GMT"
+            )])),
+            None
+        );
+    }
+}
@@ -2,17 +2,29 @@ use std::{
     marker::PhantomData,
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use bytes::Bytes;
-use eventsource_stream::{Event, EventStream, EventStreamError, Eventsource};
+use eventsource_stream::{Event, EventStreamError, Eventsource};
+#[cfg(feature = "reqwest")]
+use futures::TryStreamExt;
 use futures::{Stream, StreamExt, ready, stream::BoxStream};
+use http::{Method, header};
 use pin_project::pin_project;
-use reqwest::{Method, header};
 use serde::{Serialize, de::DeserializeOwned};
 use snafu::{ResultExt, Snafu};
+use tokio::time::{Instant, Sleep};
 
-use crate::{OpenAIError, transport::StandardHttpTransport};
+#[cfg(feature = "tracing")]
+use crate::telemetry;
+#[cfg(feature = "reqwest")]
+use crate::transport::{StandardHttpTransport, response_text};
+use crate::{
+    OpenAIError,
+    error::ApiError,
+    transport::{BoxError, RequestBody, RequestOptions, meta::ResponseMeta},
+};
 
 #[derive(Debug, Snafu)]
 pub enum OpenAIStreamingError {
@@ -22,9 +34,30 @@ pub enum OpenAIStreamingError {
         event: Event,
     },
     #[snafu(transparent)]
-    EventStream {
-        source: EventStreamError<reqwest::Error>,
-    },
+    EventStream { source: EventStreamError<BoxError> },
+    #[snafu(display("No event received for {timeout:?}"))]
+    IdleTimeout { timeout: Duration },
+    /// The server sent an error event, or an event carrying the error envelope of failed requests.
+    #[snafu(display("ApiError: {}", error.message))]
+    Api { error: Box<ApiError> },
+    /// The stream ended before the final response was received.
+    #[snafu(display("Stream ended before the response was completed"))]
+    UnexpectedEnd,
+    /// An interrupted stream could not be reopened.
+    #[snafu(display("Could not resume the stream: {source}"))]
+    Resume { source: Box<OpenAIError> },
+}
+
+impl OpenAIStreamingError {
+    /// Whether the connection was lost or stalled, so reopening the stream may succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::EventStream {
+                source: EventStreamError::Transport(_)
+            } | Self::IdleTimeout { .. }
+        )
+    }
 }
 
 pub trait StreamingTransport {
@@ -32,42 +65,270 @@ pub trait StreamingTransport {
         &self,
         method: Method,
         path: &str,
-        params: Option<&P>,
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
     ) -> impl Future<Output = Result<ParsedEventStream<E>, OpenAIError>> + Send
     where
         P: Sync + Serialize,
         E: Send + DeserializeOwned;
 }
 
+#[cfg(feature = "reqwest")]
 impl StreamingTransport for StandardHttpTransport {
     async fn send<P, E>(
         &self,
         method: Method,
         path: &str,
-        params: Option<&P>,
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
     ) -> Result<ParsedEventStream<E>, OpenAIError>
     where
         P: Sync + Serialize,
         E: Send + DeserializeOwned,
     {
-        let builder = self
-            .prepare_request(method.clone(), path, params)?
-            .header(header::ACCEPT, "text/event-stream");
+        #[cfg(feature = "tracing")]
+        let span = telemetry::http_span(
+            &method,
+            &self.endpoint(path)?,
+            &body,
+            options,
+            self.trace_bodies,
+        );
+
+        // Retries only happen before the response body is handed to the caller, so no event is ever delivered twice.
+        let response = self.execute(method, path, body, options, |builder| {
+            builder.header(header::ACCEPT, EVENT_STREAM)
+        });
+
+        #[cfg(feature = "tracing")]
+        let response = tracing::Instrument::instrument(response, span);
+
+        let response = response.await?;
+
+        // Error statuses were already turned into errors by `execute`.
+        let meta = ResponseMeta::new(response.status(), response.headers().clone());
+        if !is_event_stream(&meta) {
+            let text = response_text(response, &meta).await?;
+            return Err(unexpected_content_type(text, meta));
+        }
+
+        let bytes = response.bytes_stream().map_err(Into::into).boxed();
+        let stream = ParsedEventStream::from_bytes_stream(bytes, meta);
+
+        Ok(
+            match options.stream_idle_timeout.or(self.stream_idle_timeout) {
+                Some(timeout) => stream.with_idle_timeout(timeout),
+                None => stream,
+            },
+        )
+    }
+}
+
+/// Whether the response is an event stream according to its `content-type` header.
+#[cfg_attr(
+    not(any(feature = "reqwest", feature = "tower", feature = "mock")),
+    allow(dead_code)
+)]
+pub(crate) fn is_event_stream(meta: &ResponseMeta) -> bool {
+    meta.headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|media_type| media_type.trim().eq_ignore_ascii_case(EVENT_STREAM))
+}
 
-        Ok(ParsedEventStream {
-            inner: builder.send().await?.bytes_stream().boxed().eventsource(),
-            _marker: PhantomData::<E>,
-        })
+/// The error for a successful response to a streaming request that is not an event stream.
+#[cfg_attr(
+    not(any(feature = "reqwest", feature = "tower", feature = "mock")),
+    allow(dead_code)
+)]
+pub(crate) fn unexpected_content_type(text: String, meta: ResponseMeta) -> OpenAIError {
+    OpenAIError::UnexpectedContentType {
+        content_type: meta
+            .headers
+            .get(header::CONTENT_TYPE)
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned()),
+        text,
+        meta: Box::new(meta),
     }
 }
 
+/// A stream of raw server-sent events.
+pub type EventStream = BoxStream<'static, Result<Event, EventStreamError<BoxError>>>;
+
+/// Media type of server-sent event responses.
+#[cfg_attr(
+    not(any(feature = "reqwest", feature = "tower", feature = "mock")),
+    allow(dead_code)
+)]
+pub(crate) const EVENT_STREAM: &str = "text/event-stream";
+/// Data of the event that ends a stream, sent by some endpoints such as chat completions.
+const DONE: &str = "[DONE]";
+/// Name of events carrying an error instead of a payload.
+const ERROR_EVENT: &str = "error";
+
+/// Server-sent events with their data deserialized as `T`.
+///
+/// The stream ends at a `[DONE]` event. Events named `error` are returned as [`OpenAIStreamingError::Api`], and events without data, such as keep-alives, are skipped.
 #[pin_project]
 pub struct ParsedEventStream<T> {
     #[pin]
-    pub(crate) inner: EventStream<BoxStream<'static, Result<Bytes, reqwest::Error>>>,
+    pub(crate) inner: EventStream,
+    meta: ResponseMeta,
+    done: bool,
     _marker: PhantomData<T>,
 }
 
+/// A server-sent event with its deserialized data, as returned by [`ParsedEventStream::with_sse_fields`].
+#[derive(Debug, Clone)]
+pub struct SseEvent<T> {
+    /// The `event` field, `message` if the server didn't set one.
+    pub event: String,
+    /// The last `id` field sent by the server, if any.
+    pub id: Option<String>,
+    pub data: T,
+}
+
+impl<T> ParsedEventStream<T> {
+    /// Parses server-sent events from a response body, for use by [`StreamingTransport`] implementations.
+    pub fn from_bytes_stream(
+        bytes: BoxStream<'static, Result<Bytes, BoxError>>,
+        meta: ResponseMeta,
+    ) -> Self {
+        Self::from_event_stream(bytes.eventsource().boxed(), meta)
+    }
+
+    /// Wraps already parsed server-sent events, for use by [`StreamingTransport`] implementations.
+    pub fn from_event_stream(events: EventStream, meta: ResponseMeta) -> Self {
+        Self {
+            inner: events,
+            meta,
+            done: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Yields the event name and id of every event alongside its data.
+    pub fn with_sse_fields(self) -> SseEventStream<T> {
+        SseEventStream { inner: self }
+    }
+
+    /// Ends the stream with [`OpenAIStreamingError::IdleTimeout`] if no event arrives within `timeout` of the previous one.
+    ///
+    /// The timeout is applied to the raw events, so it is kept by [`ParsedEventStream::into_event_stream`].
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.inner = IdleTimeout {
+            inner: self.inner,
+            timeout,
+            sleep: tokio::time::sleep(timeout),
+            timed_out: false,
+        }
+        .boxed();
+        self
+    }
+
+    /// Keeps the operation span of a streamed response open until the response ends, see [`telemetry::TracedEvents`].
+    #[cfg(feature = "tracing")]
+    pub(crate) fn with_span(mut self, span: tracing::Span) -> Self {
+        self.inner = telemetry::TracedEvents::new(self.inner, span).boxed();
+        self
+    }
+
+    /// Returns the raw events and the response metadata, e.g. to observe events before handing them to [`ParsedEventStream::from_event_stream`].
+    pub fn into_event_stream(self) -> (EventStream, ResponseMeta) {
+        (self.inner, self.meta)
+    }
+
+    /// Returns the metadata of the response the events are read from.
+    pub fn meta(&self) -> &ResponseMeta {
+        &self.meta
+    }
+}
+
+impl<T> ParsedEventStream<T>
+where
+    T: DeserializeOwned,
+{
+    fn poll_event(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<SseEvent<T>, OpenAIStreamingError>>> {
+        let mut this = self.project();
+
+        loop {
+            if *this.done {
+                return Poll::Ready(None);
+            }
+
+            let event = match ready!(this.inner.as_mut().poll_next(cx)) {
+                Some(Ok(event)) => event,
+                // Errors raised by this crate, such as idle timeouts, are passed through the raw stream boxed.
+                Some(Err(EventStreamError::Transport(source))) => {
+                    return Poll::Ready(Some(match source.downcast() {
+                        Ok(err) => Err(*err),
+                        Err(source) => Err(EventStreamError::Transport(source).into()),
+                    }));
+                }
+                Some(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+                None => return Poll::Ready(None),
+            };
+
+            let data = event.data.trim();
+
+            if data.is_empty() {
+                continue;
+            }
+
+            if data == DONE {
+                *this.done = true;
+                return Poll::Ready(None);
+            }
+
+            if event.event == ERROR_EVENT {
+                return Poll::Ready(Some(Err(OpenAIStreamingError::Api {
+                    error: Box::new(parse_error(data)),
+                })));
+            }
+
+            return Poll::Ready(Some(match serde_json::from_str(data) {
+                Ok(data) => Ok(SseEvent {
+                    id: (!event.id.is_empty()).then(|| event.id.clone()),
+                    event: event.event,
+                    data,
+                }),
+                // Some endpoints send errors as regular events carrying the error envelope.
+                Err(source) => match ApiError::from_body(data) {
+                    Some(error) => Err(OpenAIStreamingError::Api {
+                        error: Box::new(error),
+                    }),
+                    None => Err(source).context(DeserializeEventDataSnafu { event }),
+                },
+            }));
+        }
+    }
+}
+
+/// Parses the data of an error event, which is either the error envelope or, as sent by the Responses API, the flattened error.
+fn parse_error(data: &str) -> ApiError {
+    if let Some(error) = ApiError::from_body(data) {
+        return error;
+    }
+
+    match serde_json::from_str::<ApiError>(data) {
+        Ok(error) => ApiError {
+            // The type of the event, not of the error
+            r#type: error.r#type.filter(|r#type| r#type != ERROR_EVENT),
+            ..error
+        },
+        Err(_) => ApiError {
+            message: data.to_string(),
+            r#type: None,
+            param: None,
+            code: None,
+        },
+    }
+}
+
 impl<T> Stream for ParsedEventStream<T>
 where
     T: DeserializeOwned,
@@ -75,12 +336,220 @@ where
     type Item = Result<T, OpenAIStreamingError>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let this = self.project();
-        Poll::Ready(ready!(this.inner.poll_next(cx)).map(|result| match result {
-            Ok(event) => {
-                serde_json::from_str(&event.data).context(DeserializeEventDataSnafu { event })
-            }
-            Err(err) => Err(err.into()),
-        }))
+        self.poll_event(cx)
+            .map(|event| event.map(|event| event.map(|event| event.data)))
+    }
+}
+
+/// Server-sent events with their event name and id, see [`ParsedEventStream::with_sse_fields`].
+#[pin_project]
+pub struct SseEventStream<T> {
+    #[pin]
+    inner: ParsedEventStream<T>,
+}
+
+impl<T> SseEventStream<T> {
+    /// Returns the metadata of the response the events are read from.
+    pub fn meta(&self) -> &ResponseMeta {
+        self.inner.meta()
+    }
+}
+
+impl<T> Stream for SseEventStream<T>
+where
+    T: DeserializeOwned,
+{
+    type Item = Result<SseEvent<T>, OpenAIStreamingError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().inner.poll_event(cx)
+    }
+}
+
+/// Ends a stream of raw events with [`OpenAIStreamingError::IdleTimeout`] if no event arrives within the timeout.
+#[pin_project]
+struct IdleTimeout {
+    #[pin]
+    inner: EventStream,
+    timeout: Duration,
+    #[pin]
+    sleep: Sleep,
+    timed_out: bool,
+}
+
+impl Stream for IdleTimeout {
+    type Item = Result<Event, EventStreamError<BoxError>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        if *this.timed_out {
+            return Poll::Ready(None);
+        }
+
+        if let Poll::Ready(item) = this.inner.poll_next(cx) {
+            let deadline = Instant::now() + *this.timeout;
+            this.sleep.reset(deadline);
+            return Poll::Ready(item);
+        }
+
+        ready!(this.sleep.as_mut().poll(cx));
+        *this.timed_out = true;
+
+        Poll::Ready(Some(Err(EventStreamError::Transport(Box::new(
+            OpenAIStreamingError::IdleTimeout {
+                timeout: *this.timeout,
+            },
+        )))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use futures::{StreamExt, stream};
+    use serde_json::Value;
+
+    use crate::transport::{
+        meta::ResponseMeta,
+        streaming::{OpenAIStreamingError, ParsedEventStream},
+    };
+
+    fn parse(body: &'static str) -> ParsedEventStream<Value> {
+        ParsedEventStream::from_bytes_stream(
+            stream::iter([Ok(body.into())]).boxed(),
+            ResponseMeta::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_done() {
+        let events: Vec<_> =
+            parse(": keep-alive\n\ndata: {\"n\": 1}\n\ndata: [DONE]\n\ndata: {\"n\": 2}\n\n")
+                .map(Result::unwrap)
+                .collect()
+                .await;
+        assert_eq!(events, [serde_json::json!({"n": 1})]);
+    }
+
+    #[tokio::test]
+    async fn test_error_events() {
+        let mut stream = parse(concat!(
+            "event: error\ndata: {\"type\": \"error\", \"code\": \"server_error\", \"message\": \"Boom\", \"param\": null}\n\n",
+            "data: {\"error\": {\"message\": \"Rate limited\", \"type\": \"requests\", \"param\": null, \"code\": \"rate_limit_exceeded\"}}\n\n",
+        ));
+
+        let Some(Err(OpenAIStreamingError::Api { error })) = stream.next().await else {
+            panic!("expected an API error");
+        };
+        assert_eq!(error.message, "Boom");
+        assert_eq!(error.r#type, None);
+
+        // Deserializes as `Value`, so the envelope is only detected for payload types it doesn't match.
+        assert!(matches!(stream.next().await, Some(Ok(_))));
+
+        let mut stream = ParsedEventStream::<Vec<u32>>::from_bytes_stream(
+            stream::iter([Ok("data: {\"error\": {\"message\": \"Rate limited\", \"type\": \"requests\", \"param\": null, \"code\": \"rate_limit_exceeded\"}}\n\n".into())]).boxed(),
+            ResponseMeta::default(),
+        );
+        let Some(Err(OpenAIStreamingError::Api { error })) = stream.next().await else {
+            panic!("expected an API error");
+        };
+        assert!(error.is_rate_limited());
+    }
+
+    #[tokio::test]
+    async fn test_sse_fields() {
+        let events: Vec<_> = parse("event: thing\nid: 1\ndata: {}\n\ndata: {}\n\n")
+            .with_sse_fields()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(events[0].event, "thing");
+        assert_eq!(events[0].id.as_deref(), Some("1"));
+        assert_eq!(events[1].event, "message");
+        assert_eq!(events[1].id.as_deref(), Some("1"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_timeout() {
+        let events = stream::iter([Ok(eventsource_stream::Event {
+            data: "{}".to_string(),
+            ..Default::default()
+        })])
+        .chain(stream::pending());
+
+        let mut stream =
+            ParsedEventStream::<Value>::from_event_stream(events.boxed(), ResponseMeta::default())
+                .with_idle_timeout(Duration::from_secs(5));
+
+        assert!(matches!(stream.next().await, Some(Ok(_))));
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(OpenAIStreamingError::IdleTimeout { .. }))
+        ));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn test_standard_http_not_an_event_stream() {
+        use http::{Method, StatusCode};
+
+        use crate::{
+            OpenAIError,
+            transport::{
+                RequestBody, RequestOptions, StandardHttpTransport,
+                streaming::StreamingTransport,
+                test::{http_response, serve},
+            },
+        };
+
+        let (address, server) = serve(vec![
+            http_response(StatusCode::OK, "application/json", r#"{"id":"resp_123"}"#),
+            http_response(
+                StatusCode::UNAUTHORIZED,
+                "application/json",
+                r#"{"error":{"message":"Invalid API key","type":"invalid_request_error","param":null,"code":"invalid_api_key"}}"#,
+            ),
+        ])
+        .await;
+
+        let transport = StandardHttpTransport::builder("sk-test".into())
+            .base_url(format!("http://{address}").parse().unwrap())
+            .build()
+            .unwrap();
+        let options = RequestOptions::default();
+        let send = || {
+            StreamingTransport::send::<(), Value>(
+                &transport,
+                Method::GET,
+                "/v1/responses/resp_123",
+                RequestBody::none(),
+                &options,
+            )
+        };
+
+        let Err(OpenAIError::UnexpectedContentType {
+            content_type, text, ..
+        }) = send().await
+        else {
+            panic!("expected an unexpected content type error");
+        };
+        assert_eq!(content_type.as_deref(), Some("application/json"));
+        assert_eq!(text, r#"{"id":"resp_123"}"#);
+
+        let Err(err) = send().await else {
+            panic!("expected an API error");
+        };
+        assert!(
+            matches!(err, OpenAIError::Api { status, .. } if status == StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(err.api_error().unwrap().message, "Invalid API key");
+
+        let requests = server.await.unwrap();
+        assert!(requests[0].contains("accept: text/event-stream\r\n"));
     }
 }
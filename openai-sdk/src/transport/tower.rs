@@ -0,0 +1,939 @@
+//! Adapters between [`Transport`] and [tower](https://docs.rs/tower) services, so existing tower layers (rate limiting, concurrency limits, load shedding, auth refresh) can be reused.
+
+use std::{sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use http::{
+    Method,
+    header::{self, HeaderMap, HeaderValue},
+};
+use http_body::Body;
+use http_body_util::BodyExt;
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Serialize, de::DeserializeOwned};
+use snafu::ResultExt;
+use tokio::time::Instant;
+use tower::{Service, ServiceExt};
+use url::Url;
+
+#[cfg(feature = "tracing")]
+use crate::telemetry;
+use crate::{
+    OpenAIError, SerializeRequestSnafu,
+    error::TimeoutKind,
+    transport::{
+        BoxError, DEFAULT_BASE_URL, ORGANIZATION_HEADER, PROJECT_HEADER, RequestBody,
+        RequestOptions, Transport,
+        credentials::CredentialProvider,
+        deserialize_response,
+        meta::{ResponseMeta, WithMeta},
+        retry::RetryPolicy,
+        with_trailing_slash,
+    },
+};
+
+/// A [`Transport`] sending requests through a tower service.
+///
+/// The service receives fully encoded requests with absolute URIs and is expected to handle connections itself, e.g. a hyper client wrapped in tower layers. Anything else the service should add to every request can be added with layers as well.
+///
+/// Service errors are reported as [`OpenAIError::Transport`], unless the service fails with an [`OpenAIError`], which is passed through as is.
+#[derive(Clone)]
+pub struct TowerTransport<S> {
+    service: S,
+    base_url: Url,
+    credentials: Option<Arc<dyn CredentialProvider>>,
+    retry: RetryPolicy,
+    organization: Option<String>,
+    project: Option<String>,
+    default_headers: HeaderMap,
+    timeout: Option<Duration>,
+    #[cfg(feature = "tracing")]
+    trace_bodies: bool,
+}
+
+impl<S> TowerTransport<S> {
+    pub fn new(service: S) -> Self {
+        Self {
+            service,
+            base_url: DEFAULT_BASE_URL.parse().unwrap(),
+            credentials: None,
+            retry: RetryPolicy::default(),
+            organization: None,
+            project: None,
+            default_headers: HeaderMap::new(),
+            timeout: None,
+            #[cfg(feature = "tracing")]
+            trace_bodies: false,
+        }
+    }
+
+    /// Sets the URL endpoint paths are resolved against. Default: `https://api.openai.com/`
+    pub fn base_url(mut self, base_url: Url) -> Self {
+        self.base_url = with_trailing_slash(base_url);
+        self
+    }
+
+    /// Sets the token sent in the `Authorization` header. Leave unset if a layer of the service authenticates requests.
+    pub fn access_token(self, access_token: SecretString) -> Self {
+        self.credentials(access_token)
+    }
+
+    /// Sets the provider asked for the token sent in the `Authorization` header of every request.
+    pub fn credentials(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        self.credentials = Some(Arc::new(credentials));
+        self
+    }
+
+    /// Sets the retry policy used for requests that don't override it. Default: [`RetryPolicy::default`]
+    ///
+    /// Use [`RetryPolicy::none`] if the service retries by itself.
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets the organization requests are attributed to, sent as the `OpenAI-Organization` header.
+    pub fn organization(mut self, organization: impl Into<String>) -> Self {
+        self.organization = Some(organization.into());
+        self
+    }
+
+    /// Sets the project requests are attributed to, sent as the `OpenAI-Project` header.
+    pub fn project(mut self, project: impl Into<String>) -> Self {
+        self.project = Some(project.into());
+        self
+    }
+
+    /// Sets headers sent with every request.
+    pub fn default_headers(mut self, default_headers: HeaderMap) -> Self {
+        self.default_headers = default_headers;
+        self
+    }
+
+    /// Sets the total timeout of requests that don't override it. See [`RequestOptions::timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Records request and response bodies in the `http.request.body` and `http.response.body` span fields. Bodies may contain sensitive data, so this is off by default.
+    #[cfg(feature = "tracing")]
+    pub fn trace_bodies(mut self, trace_bodies: bool) -> Self {
+        self.trace_bodies = trace_bodies;
+        self
+    }
+
+    pub fn service(&self) -> &S {
+        &self.service
+    }
+
+    pub fn into_inner(self) -> S {
+        self.service
+    }
+
+    /// Resolves an endpoint path against the base URL, keeping any path prefix of the base URL.
+    fn endpoint(&self, path: &str) -> Result<Url, OpenAIError> {
+        Ok(self.base_url.join(path.trim_start_matches('/'))?)
+    }
+
+    /// Encodes a request the way [`StandardHttpTransport`](crate::transport::StandardHttpTransport) would send it.
+    fn request<P>(
+        &self,
+        method: Method,
+        path: &str,
+        body: &RequestBody<'_, P>,
+        options: &RequestOptions,
+        access_token: Option<&SecretString>,
+    ) -> Result<http::Request<Bytes>, OpenAIError>
+    where
+        P: Serialize,
+    {
+        let mut url = self.endpoint(path)?;
+
+        let query = body.query()?;
+        if !query.is_empty() || !options.extra_query.is_empty() {
+            url.query_pairs_mut()
+                .extend_pairs(query.iter().chain(&options.extra_query));
+        }
+
+        let (content_type, bytes) = match body {
+            RequestBody::Multipart(form) => {
+                let (content_type, bytes) = form.encode()?;
+                (Some(content_type), bytes)
+            }
+            RequestBody::Raw {
+                content_type,
+                bytes,
+            } => (Some(content_type.to_string()), bytes.clone()),
+            _ => match body.json(&options.extra_body)? {
+                Some(json) => (
+                    Some("application/json".to_string()),
+                    serde_json::to_vec(&json)
+                        .context(SerializeRequestSnafu)?
+                        .into(),
+                ),
+                None => (None, Bytes::new()),
+            },
+        };
+
+        let mut headers = self.default_headers.clone();
+
+        if let Some(access_token) = access_token {
+            let mut value = header_value(&format!("Bearer {}", access_token.expose_secret()))?;
+            value.set_sensitive(true);
+            headers.insert(header::AUTHORIZATION, value);
+        }
+
+        if let Some(content_type) = content_type {
+            headers.insert(header::CONTENT_TYPE, header_value(&content_type)?);
+        }
+
+        if let Some(organization) = options.organization.as_ref().or(self.organization.as_ref()) {
+            headers.insert(ORGANIZATION_HEADER, header_value(organization)?);
+        }
+
+        if let Some(project) = options.project.as_ref().or(self.project.as_ref()) {
+            headers.insert(PROJECT_HEADER, header_value(project)?);
+        }
+
+        headers.extend(options.extra_headers.clone());
+
+        let mut request = http::Request::new(bytes);
+        *request.method_mut() = method;
+        *request.uri_mut() =
+            url.as_str()
+                .parse()
+                .map_err(|err: http::uri::InvalidUri| OpenAIError::Transport {
+                    source: err.into(),
+                    meta: None,
+                })?;
+        *request.headers_mut() = headers;
+
+        Ok(request)
+    }
+}
+
+impl<S, B> TowerTransport<S>
+where
+    S: Service<http::Request<Bytes>, Response = http::Response<B>> + Clone + Send + Sync,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+    B: Body<Data = Bytes> + Send,
+    B::Error: Into<BoxError>,
+{
+    async fn call(&self, request: http::Request<Bytes>) -> Result<http::Response<B>, OpenAIError> {
+        self.service
+            .clone()
+            .oneshot(request)
+            .await
+            .map_err(|err| transport_error(err.into()))
+    }
+
+    /// Sends the request, retrying according to the effective retry policy. Responses with a non-success status are turned into [`OpenAIError::Api`].
+    ///
+    /// [`RequestOptions::timeout`] bounds each attempt. Returns the deadline of the successful attempt, which the caller applies to reading the body.
+    async fn execute<P>(
+        &self,
+        method: Method,
+        path: &str,
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
+        accept: Option<&'static str>,
+    ) -> Result<(http::Response<B>, Option<Instant>), OpenAIError>
+    where
+        P: Sync + Serialize,
+    {
+        let policy = options.retry.as_ref().unwrap_or(&self.retry);
+        let mut attempt = 1;
+
+        loop {
+            #[cfg(feature = "tracing")]
+            telemetry::record_attempt(attempt);
+
+            let access_token = match &self.credentials {
+                Some(credentials) => Some(credentials.credential().await?),
+                None => None,
+            };
+            let mut request =
+                self.request(method.clone(), path, &body, options, access_token.as_ref())?;
+
+            if let Some(accept) = accept {
+                request
+                    .headers_mut()
+                    .insert(header::ACCEPT, HeaderValue::from_static(accept));
+            }
+
+            let deadline = options
+                .timeout
+                .or(self.timeout)
+                .map(|timeout| Instant::now() + timeout);
+            let result = with_deadline(deadline, self.call(request)).await;
+
+            let err = match result {
+                Ok(response) if response.status().is_success() => {
+                    #[cfg(feature = "tracing")]
+                    telemetry::record_response(response.status(), response.headers());
+
+                    return Ok((response, deadline));
+                }
+                Ok(response) => {
+                    let (parts, body) = response.into_parts();
+                    let meta = ResponseMeta::new(parts.status, parts.headers);
+                    OpenAIError::api(read_text(body, deadline, &meta).await?, meta)
+                }
+                Err(err) => err,
+            };
+
+            if attempt >= policy.max_attempts || !policy.retries(&err) {
+                #[cfg(feature = "tracing")]
+                telemetry::record_error(&tracing::Span::current(), &err);
+
+                return Err(err);
+            }
+
+            let headers = err.meta().map(|meta| &meta.headers);
+            tokio::time::sleep(policy.delay(attempt, headers)).await;
+            attempt += 1;
+        }
+    }
+}
+
+impl<S, B> Transport for TowerTransport<S>
+where
+    S: Service<http::Request<Bytes>, Response = http::Response<B>> + Clone + Send + Sync,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+    B: Body<Data = Bytes> + Send,
+    B::Error: Into<BoxError>,
+{
+    async fn send<P, R>(
+        &self,
+        method: Method,
+        path: &str,
+        body: RequestBody<'_, P>,
+        options: &RequestOptions,
+    ) -> Result<WithMeta<R>, OpenAIError>
+    where
+        P: Sync + Serialize,
+        R: DeserializeOwned,
+    {
+        #[cfg(feature = "tracing")]
+        let span = telemetry::http_span(
+            &method,
+            &self.endpoint(path)?,
+            &body,
+            options,
+            self.trace_bodies,
+        );
+
+        let response = async {
+            let (response, deadline) = self.execute(method, path, body, options, None).await?;
+            let (parts, body) = response.into_parts();
+            let meta = ResponseMeta::new(parts.status, parts.headers);
+            let text = read_text(body, deadline, &meta).await?;
+
+            #[cfg(feature = "tracing")]
+            telemetry::record_response_body(&text, self.trace_bodies);
+
+            deserialize_response(text, meta)
+        };
+
+        #[cfg(feature = "tracing")]
+        let response = tracing::Instrument::instrument(response, span);
+
+        response.await
+    }
+}
+
+#[cfg(feature = "responses-streaming")]
+mod streaming {
+    use bytes::Bytes;
+    use futures::{
+        StreamExt, TryStreamExt,
+        future::{self, Either},
+        stream::{self, BoxStream},
+    };
+    use http::Method;
+    use http_body::Body;
+    use http_body_util::BodyDataStream;
+    use serde::{Serialize, de::DeserializeOwned};
+    use tokio::time::Instant;
+    use tower::Service;
+
+    #[cfg(feature = "tracing")]
+    use crate::telemetry;
+    use crate::{
+        OpenAIError,
+        error::TimeoutKind,
+        transport::{
+            BoxError, RequestBody, RequestOptions,
+            meta::ResponseMeta,
+            streaming::{
+                EVENT_STREAM, ParsedEventStream, StreamingTransport, is_event_stream,
+                unexpected_content_type,
+            },
+            tower::{TowerTransport, read_text},
+        },
+    };
+
+    impl<S, B> StreamingTransport for TowerTransport<S>
+    where
+        S: Service<http::Request<Bytes>, Response = http::Response<B>> + Clone + Send + Sync,
+        S::Future: Send,
+        S::Error: Into<BoxError>,
+        B: Body<Data = Bytes> + Send + 'static,
+        B::Error: Into<BoxError>,
+    {
+        async fn send<P, E>(
+            &self,
+            method: Method,
+            path: &str,
+            body: RequestBody<'_, P>,
+            options: &RequestOptions,
+        ) -> Result<ParsedEventStream<E>, OpenAIError>
+        where
+            P: Sync + Serialize,
+            E: Send + DeserializeOwned,
+        {
+            #[cfg(feature = "tracing")]
+            let span = telemetry::http_span(
+                &method,
+                &self.endpoint(path)?,
+                &body,
+                options,
+                self.trace_bodies,
+            );
+
+            let response = self.execute(method, path, body, options, Some(EVENT_STREAM));
+
+            #[cfg(feature = "tracing")]
+            let response = tracing::Instrument::instrument(response, span);
+
+            let (response, deadline) = response.await?;
+            let (parts, body) = response.into_parts();
+            let meta = ResponseMeta::new(parts.status, parts.headers);
+            if !is_event_stream(&meta) {
+                let text = read_text(body, deadline, &meta).await?;
+                return Err(unexpected_content_type(text, meta));
+            }
+
+            let bytes = BodyDataStream::new(body).map_err(Into::into).boxed();
+            let bytes = match deadline {
+                Some(deadline) => until_deadline(bytes, deadline),
+                None => bytes,
+            };
+            let stream = ParsedEventStream::from_bytes_stream(bytes, meta);
+
+            Ok(match options.stream_idle_timeout {
+                Some(timeout) => stream.with_idle_timeout(timeout),
+                None => stream,
+            })
+        }
+    }
+
+    /// Ends the body with a request timeout at the deadline, so the timeout bounds the whole stream as with the reqwest transport.
+    fn until_deadline(
+        bytes: BoxStream<'static, Result<Bytes, BoxError>>,
+        deadline: Instant,
+    ) -> BoxStream<'static, Result<Bytes, BoxError>> {
+        let sleep = Box::pin(tokio::time::sleep_until(deadline));
+
+        stream::unfold(Some((bytes, sleep)), |state| async move {
+            let (mut bytes, mut sleep) = state?;
+
+            let next = match future::select(bytes.next(), sleep.as_mut()).await {
+                Either::Left((next, _)) => next,
+                Either::Right(_) => {
+                    let err = OpenAIError::Timeout {
+                        kind: TimeoutKind::Request,
+                        meta: None,
+                    };
+                    return Some((Err(err.into()), None));
+                }
+            };
+
+            next.map(|chunk| (chunk, Some((bytes, sleep))))
+        })
+        .boxed()
+    }
+}
+
+#[cfg(feature = "reqwest")]
+mod standard {
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use tower::Service;
+    use url::Url;
+
+    use crate::{
+        OpenAIError,
+        transport::{ORGANIZATION_HEADER, PROJECT_HEADER, StandardHttpTransport},
+    };
+
+    /// Exposes the transport as a tower service sending raw HTTP requests.
+    ///
+    /// Requests with a relative URI are resolved against the base URL. The access token, organization, project and default headers are added unless the request sets them itself. Requests are sent once, leaving retries to the surrounding layers.
+    impl<B> Service<http::Request<B>> for StandardHttpTransport
+    where
+        B: Into<reqwest::Body>,
+    {
+        type Response = http::Response<reqwest::Body>;
+        type Error = OpenAIError;
+        type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: http::Request<B>) -> Self::Future {
+            let (parts, body) = request.into_parts();
+            let body: reqwest::Body = body.into();
+            let transport = self.clone();
+
+            Box::pin(async move {
+                let url = match parts.uri.authority() {
+                    Some(_) => Url::parse(&parts.uri.to_string())?,
+                    None => transport
+                        .endpoint(parts.uri.path_and_query().map_or("/", |path| path.as_str()))?,
+                };
+
+                let access_token = transport.credentials.credential().await?;
+
+                let mut builder = transport.client.request(parts.method, url);
+                builder = transport.auth_scheme.authenticate(builder, &access_token)?;
+                builder = builder.headers(transport.default_headers.clone());
+
+                if let Some(organization) = &transport.organization {
+                    builder = builder.header(ORGANIZATION_HEADER, organization);
+                }
+
+                if let Some(project) = &transport.project {
+                    builder = builder.header(PROJECT_HEADER, project);
+                }
+
+                if let Some(timeout) = transport.timeout {
+                    builder = builder.timeout(timeout);
+                }
+
+                let response = builder.headers(parts.headers).body(body).send().await?;
+
+                Ok(response.into())
+            })
+        }
+    }
+}
+
+/// Unwraps errors of services that already fail with an [`OpenAIError`], such as [`StandardHttpTransport`](crate::transport::StandardHttpTransport).
+fn transport_error(source: BoxError) -> OpenAIError {
+    match source.downcast::<OpenAIError>() {
+        Ok(err) => *err,
+        Err(source) => OpenAIError::Transport { source, meta: None },
+    }
+}
+
+fn header_value(value: &str) -> Result<HeaderValue, OpenAIError> {
+    HeaderValue::from_str(value).map_err(|err| OpenAIError::Transport {
+        source: err.into(),
+        meta: None,
+    })
+}
+
+/// Fails with a request timeout if the future doesn't complete before the deadline.
+async fn with_deadline<T>(
+    deadline: Option<Instant>,
+    future: impl Future<Output = Result<T, OpenAIError>>,
+) -> Result<T, OpenAIError> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, future)
+            .await
+            .unwrap_or(Err(OpenAIError::Timeout {
+                kind: TimeoutKind::Request,
+                meta: None,
+            })),
+        None => future.await,
+    }
+}
+
+/// Reads the body of a response before the deadline, attaching the response metadata to errors.
+async fn read_text<B>(
+    body: B,
+    deadline: Option<Instant>,
+    meta: &ResponseMeta,
+) -> Result<String, OpenAIError>
+where
+    B: Body<Data = Bytes>,
+    B::Error: Into<BoxError>,
+{
+    let collect = async {
+        body.collect()
+            .await
+            .map_err(|err| transport_error(err.into()))
+    };
+    let bytes = with_deadline(deadline, collect)
+        .await
+        .map_err(|err| err.with_meta(meta))?
+        .to_bytes();
+
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+        time::Duration,
+    };
+
+    use bytes::Bytes;
+    use http::{
+        Method, StatusCode,
+        header::{self, HeaderMap, HeaderValue},
+    };
+    use http_body::Frame;
+    use serde_json::{Value, json};
+
+    use crate::{
+        OpenAIError,
+        error::TimeoutKind,
+        transport::{
+            ORGANIZATION_HEADER, PROJECT_HEADER, RequestBody, RequestOptions, Transport,
+            retry::RetryPolicy, tower::TowerTransport,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_tower_transport() {
+        let service = tower::service_fn(|request: http::Request<Bytes>| async move {
+            assert_eq!(request.method(), Method::POST);
+            assert_eq!(
+                request.uri(),
+                "https://gateway.internal/openai/v1/responses?trace=1"
+            );
+            assert_eq!(request.headers()[header::AUTHORIZATION], "Bearer sk-test");
+            assert_eq!(request.headers()[header::CONTENT_TYPE], "application/json");
+            assert_eq!(
+                serde_json::from_slice::<Value>(request.body()).unwrap(),
+                json!({ "input": "hi", "store": false })
+            );
+
+            Ok::<_, OpenAIError>(
+                http::Response::builder()
+                    .header("x-request-id", "req_123")
+                    .body(r#"{"id":"resp_123"}"#.to_string())
+                    .unwrap(),
+            )
+        });
+
+        let transport = TowerTransport::new(service)
+            .base_url("https://gateway.internal/openai".parse().unwrap())
+            .access_token("sk-test".into());
+
+        let options = RequestOptions {
+            extra_query: vec![("trace".to_string(), "1".to_string())],
+            extra_body: json!({ "store": false }).as_object().unwrap().clone(),
+            ..Default::default()
+        };
+
+        let response = Transport::send::<_, Value>(
+            &transport,
+            Method::POST,
+            "/v1/responses",
+            RequestBody::Json(&json!({ "input": "hi" })),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.data, json!({ "id": "resp_123" }));
+        assert_eq!(response.meta.request_id.as_deref(), Some("req_123"));
+    }
+
+    #[tokio::test]
+    async fn test_tower_transport_error() {
+        let service = tower::service_fn(|_: http::Request<Bytes>| async {
+            Ok::<_, OpenAIError>(
+                http::Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(r#"{"error":{"message":"Not found","type":"invalid_request_error","param":null,"code":null}}"#.to_string())
+                    .unwrap(),
+            )
+        });
+
+        let transport = TowerTransport::new(service).retry_policy(RetryPolicy::none());
+
+        let err = Transport::send::<_, Value>(
+            &transport,
+            Method::GET,
+            "/v1/responses/resp_123",
+            RequestBody::none(),
+            &RequestOptions::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, OpenAIError::Api { status, .. } if status == StatusCode::NOT_FOUND));
+        assert_eq!(err.api_error().unwrap().message, "Not found");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_tower_transport_defaults() {
+        let service = tower::service_fn(|request: http::Request<Bytes>| async move {
+            let headers = request.headers();
+            assert_eq!(headers[ORGANIZATION_HEADER], "org_456");
+            assert_eq!(headers[PROJECT_HEADER], "proj_123");
+            assert_eq!(headers["x-gateway-key"], "key_123");
+
+            if request.uri().path() == "/v1/slow" {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            }
+
+            Ok::<_, OpenAIError>(http::Response::new("{}".to_string()))
+        });
+
+        let mut default_headers = HeaderMap::new();
+        default_headers.insert("x-gateway-key", HeaderValue::from_static("key_123"));
+
+        let transport = TowerTransport::new(service)
+            .organization("org_123")
+            .project("proj_123")
+            .default_headers(default_headers)
+            .timeout(Duration::from_secs(5))
+            .retry_policy(RetryPolicy::none());
+
+        let options = RequestOptions {
+            organization: Some("org_456".to_string()),
+            ..Default::default()
+        };
+
+        Transport::send::<_, Value>(
+            &transport,
+            Method::GET,
+            "/v1/models",
+            RequestBody::none(),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        let err = Transport::send::<_, Value>(
+            &transport,
+            Method::GET,
+            "/v1/slow",
+            RequestBody::none(),
+            &options,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            OpenAIError::Timeout {
+                kind: TimeoutKind::Request,
+                ..
+            }
+        ));
+    }
+
+    #[cfg(feature = "responses-streaming")]
+    #[tokio::test]
+    async fn test_tower_transport_not_an_event_stream() {
+        use crate::transport::streaming::StreamingTransport;
+
+        let service = tower::service_fn(|request: http::Request<Bytes>| async move {
+            assert_eq!(request.headers()[header::ACCEPT], "text/event-stream");
+
+            let response = match request.uri().path() {
+                "/v1/responses/resp_123" => http::Response::builder()
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(r#"{"id":"resp_123"}"#.to_string()),
+                _ => http::Response::builder()
+                    .status(StatusCode::UNAUTHORIZED)
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(r#"{"error":{"message":"Invalid API key","type":"invalid_request_error","param":null,"code":"invalid_api_key"}}"#.to_string()),
+            };
+
+            Ok::<_, OpenAIError>(response.unwrap())
+        });
+
+        let transport = TowerTransport::new(service).retry_policy(RetryPolicy::none());
+        let options = RequestOptions::default();
+        let send = |path| {
+            StreamingTransport::send::<(), Value>(
+                &transport,
+                Method::GET,
+                path,
+                RequestBody::none(),
+                &options,
+            )
+        };
+
+        let Err(OpenAIError::UnexpectedContentType {
+            content_type, text, ..
+        }) = send("/v1/responses/resp_123").await
+        else {
+            panic!("expected an unexpected content type error");
+        };
+        assert_eq!(content_type.as_deref(), Some("application/json"));
+        assert_eq!(text, r#"{"id":"resp_123"}"#);
+
+        let Err(err) = send("/v1/responses/resp_456").await else {
+            panic!("expected an API error");
+        };
+        assert!(
+            matches!(err, OpenAIError::Api { status, .. } if status == StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(err.api_error().unwrap().message, "Invalid API key");
+    }
+
+    /// A response body that never yields a frame.
+    struct PendingBody;
+
+    impl http_body::Body for PendingBody {
+        type Data = Bytes;
+        type Error = OpenAIError;
+
+        fn poll_frame(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Frame<Bytes>, OpenAIError>>> {
+            Poll::Pending
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_tower_transport_body_timeout() {
+        let service = tower::service_fn(|_: http::Request<Bytes>| async {
+            Ok::<_, OpenAIError>(
+                http::Response::builder()
+                    .header("x-request-id", "req_123")
+                    .body(PendingBody)
+                    .unwrap(),
+            )
+        });
+
+        let transport = TowerTransport::new(service).retry_policy(RetryPolicy::none());
+        let options = RequestOptions {
+            timeout: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+
+        let err = Transport::send::<_, Value>(
+            &transport,
+            Method::GET,
+            "/v1/responses/resp_123",
+            RequestBody::none(),
+            &options,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(
+            err,
+            OpenAIError::Timeout {
+                kind: TimeoutKind::Request,
+                ..
+            }
+        ));
+        // The head was received before the body timed out.
+        assert_eq!(err.request_id(), Some("req_123"));
+    }
+
+    #[cfg(feature = "responses-streaming")]
+    #[tokio::test(start_paused = true)]
+    async fn test_tower_transport_streaming() {
+        use futures::StreamExt;
+        use http_body_util::StreamBody;
+
+        use crate::transport::streaming::{OpenAIStreamingError, StreamingTransport};
+
+        let service = tower::service_fn(|request: http::Request<Bytes>| async move {
+            assert_eq!(request.headers()[header::ACCEPT], "text/event-stream");
+
+            // The last event never arrives.
+            let frames = futures::stream::iter([
+                Ok(Frame::data(Bytes::from("data: {\"n\": 1}\n\n"))),
+                Ok(Frame::data(Bytes::from("data: {\"n\": 2}\n\n"))),
+            ])
+            .chain(futures::stream::pending::<Result<_, OpenAIError>>());
+
+            Ok::<_, OpenAIError>(
+                http::Response::builder()
+                    .header(header::CONTENT_TYPE, "text/event-stream; charset=utf-8")
+                    .body(StreamBody::new(frames))
+                    .unwrap(),
+            )
+        });
+
+        let transport = TowerTransport::new(service);
+        let options = RequestOptions {
+            timeout: Some(Duration::from_secs(30)),
+            ..Default::default()
+        };
+
+        let mut stream = StreamingTransport::send::<_, Value>(
+            &transport,
+            Method::POST,
+            "/v1/responses",
+            RequestBody::Json(&json!({ "stream": true })),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), json!({ "n": 1 }));
+        assert_eq!(stream.next().await.unwrap().unwrap(), json!({ "n": 2 }));
+
+        // The timeout bounds the whole stream.
+        let Some(Err(OpenAIStreamingError::EventStream { source })) = stream.next().await else {
+            panic!("expected the stream to time out");
+        };
+        assert!(source.to_string().contains("timed out"));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn test_standard_http_service() {
+        use http_body_util::BodyExt;
+        use tower::ServiceExt;
+
+        use crate::transport::{
+            StandardHttpTransport,
+            test::{http_response, serve},
+        };
+
+        let (address, server) = serve(vec![http_response(
+            StatusCode::OK,
+            "application/json",
+            r#"{"object":"list","data":[]}"#,
+        )])
+        .await;
+
+        let transport = StandardHttpTransport::builder("sk-test".into())
+            .base_url(format!("http://{address}/openai").parse().unwrap())
+            .organization("org-123")
+            .build()
+            .unwrap();
+
+        let request = http::Request::builder()
+            .uri("/v1/models?limit=1")
+            .header("openai-organization", "org-override")
+            .body(String::new())
+            .unwrap();
+        let response = transport.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, r#"{"object":"list","data":[]}"#);
+
+        let request = server.await.unwrap().remove(0).to_lowercase();
+        assert!(request.starts_with("get /openai/v1/models?limit=1 http/1.1\r\n"));
+        assert!(request.contains("authorization: bearer sk-test\r\n"));
+        // Headers set on the request win over the transport defaults.
+        assert!(request.contains("openai-organization: org-override\r\n"));
+        assert!(!request.contains("org-123"));
+    }
+}